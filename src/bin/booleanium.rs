@@ -1,25 +1,14 @@
-use booleanium::{
-    cli,
-    incdet::IncDet,
-    qdimacs::{ExtendedParseError, QdimacsParser},
-    SolverResult,
-};
+use booleanium::cli;
 use miette::Result;
-use std::io::Cursor;
+use std::process::{ExitCode, Termination};
 
-fn main() -> Result<SolverResult> {
+fn main() -> Result<ExitCode> {
     tracing_subscriber::fmt::init();
+    cli::install_report_handler()?;
 
-    let contents = cli::content_from_args()?;
-    let reader = Cursor::new(&contents);
-
-    let mut solver: IncDet = match QdimacsParser::new(reader).parse() {
-        Ok(q) => q,
-        Err(err) => Err(ExtendedParseError { source_code: contents, related: vec![err] })?,
-    };
-
-    let result = solver.solve();
-    println!("result status: {}", result);
-
-    Ok(result)
+    if std::env::args().nth(1).as_deref() == Some(cli::CHECK_FLAG) {
+        cli::check_from_args()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    booleanium::run_on_stdin().map(Termination::report)
 }
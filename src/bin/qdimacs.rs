@@ -9,6 +9,7 @@ fn main() -> Result<()> {
     use booleanium::cli;
 
     tracing_subscriber::fmt::init();
+    cli::install_report_handler()?;
 
     let contents = cli::content_from_args()?;
     let reader = Cursor::new(&contents);
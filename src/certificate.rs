@@ -0,0 +1,365 @@
+//! Independent verification of Skolem certificates for satisfiable formulas.
+//!
+//! A certificate assigns a [`SkolemFn`] to every existential variable.
+//! It is valid if substituting the functions into the matrix yields a formula
+//! that is satisfied by every assignment of the universal variables.
+//! This is checked by a SAT solver: the definitions of the functions conjoined with
+//! the negated matrix are unsatisfiable iff the certificate is valid.
+
+use crate::{
+    incdet::SkolemFn,
+    literal::Var,
+    qcnf::QCNF,
+    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    QuantTy,
+};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, error};
+
+/// A Skolem function for every existential variable of a formula, see [`verify_skolem`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkolemCertificate {
+    functions: Vec<SkolemFn>,
+}
+
+impl SkolemCertificate {
+    #[must_use]
+    pub fn new(functions: Vec<SkolemFn>) -> Self {
+        Self { functions }
+    }
+
+    #[must_use]
+    pub fn functions(&self) -> &[SkolemFn] {
+        &self.functions
+    }
+}
+
+impl FromIterator<SkolemFn> for SkolemCertificate {
+    fn from_iter<T: IntoIterator<Item = SkolemFn>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Checks whether `cert` is a valid Skolem certificate for `qcnf`, i.e., whether the formula is
+/// satisfied for every universal assignment if the existentials are assigned by their functions.
+///
+/// A function is evaluated as in [`SkolemFn::eval`], where the value is `false` if no case applies.
+/// The conditions of a function may only contain universal variables bound before the existential
+/// and existential variables bound in the same scope or before, as long as the dependencies are acyclic.
+/// Free variables are existentially quantified in the outermost scope.
+///
+/// Returns `false` if the certificate is not valid, misses a function for an existential variable,
+/// or a function violates the dependencies of the quantifier prefix.
+#[must_use]
+pub fn verify_skolem(qcnf: &QCNF, cert: &SkolemCertificate) -> bool {
+    let mut levels: HashMap<Var, (usize, QuantTy)> = HashMap::new();
+    for (idx, (quant, vars)) in qcnf.prefix.iter().enumerate() {
+        levels.extend(vars.iter().map(|&var| (var, (idx + 1, *quant))));
+    }
+    let level = |var: Var| levels.get(&var).copied().unwrap_or((0, QuantTy::Exists));
+
+    let mut functions: HashMap<Var, &SkolemFn> = HashMap::new();
+    for function in cert.functions() {
+        let var = function.var();
+        if level(var).1 != QuantTy::Exists || functions.insert(var, function).is_some() {
+            debug!("certificate contains an invalid or duplicate function for {var}");
+            return false;
+        }
+    }
+    let existentials = qcnf
+        .prefix
+        .iter()
+        .filter(|(quant, _)| *quant == QuantTy::Exists)
+        .flat_map(|(_, vars)| vars.iter().copied())
+        .chain(qcnf.matrix.iter().flatten().map(|lit| lit.var()))
+        .filter(|&var| level(var).1 == QuantTy::Exists);
+    for var in existentials {
+        if !functions.contains_key(&var) {
+            debug!("certificate misses a function for {var}");
+            return false;
+        }
+    }
+
+    // the dependencies have to respect the prefix and must be acyclic
+    let mut dependencies: HashMap<Var, HashSet<Var>> = HashMap::new();
+    for (&var, function) in &functions {
+        let (var_level, _) = level(var);
+        for lit in function.cases().iter().flat_map(|(condition, _)| condition) {
+            let dependency = lit.var();
+            match level(dependency) {
+                (dep_level, QuantTy::Forall) if dep_level < var_level => {}
+                (dep_level, QuantTy::Exists) if dep_level <= var_level && dependency != var => {
+                    if !functions.contains_key(&dependency) {
+                        debug!("function of {var} depends on {dependency} without a function");
+                        return false;
+                    }
+                    dependencies.entry(var).or_default().insert(dependency);
+                }
+                _ => {
+                    debug!("function of {var} must not depend on {dependency}");
+                    return false;
+                }
+            }
+        }
+    }
+    if has_cycle(&dependencies) {
+        debug!("certificate contains cyclic dependencies");
+        return false;
+    }
+
+    // functions may define or depend on variables that do not occur in the formula
+    let cert_vars = functions.values().flat_map(|function| {
+        let conditions = function.cases().iter().flat_map(|(condition, _)| condition);
+        conditions.map(|lit| lit.var()).chain(std::iter::once(function.var()))
+    });
+    let var_count = cert_vars
+        .map(|var| usize::try_from(var.to_dimacs()).unwrap())
+        .chain(std::iter::once(usize::try_from(qcnf.num_variables()).unwrap()))
+        .max()
+        .unwrap_or_default();
+    let mut solver = LookupSolver::<Varisat>::default();
+    solver.set_var_count(var_count);
+    for function in functions.values() {
+        add_definition(&mut solver, function);
+    }
+    // the negation of the matrix, i.e., some clause is falsified
+    let mut falsified = Vec::new();
+    for clause in &qcnf.matrix {
+        let is_falsified = solver.add_variable();
+        for &lit in clause {
+            let lit = solver.lookup(lit);
+            solver.add_clause(&[!is_falsified, !lit]);
+        }
+        falsified.push(is_falsified);
+    }
+    solver.add_clause(&falsified);
+
+    match solver.solve() {
+        Ok(is_sat) => !is_sat,
+        Err(err) => {
+            error!("SAT solver failed: {err}");
+            false
+        }
+    }
+}
+
+/// Adds the clauses defining the variable of `function` as the if-then-else chain over its cases,
+/// where the value is `false` if no case applies.
+fn add_definition<S: SatSolver>(solver: &mut LookupSolver<S>, function: &SkolemFn) {
+    // `rest` is the value of the chain starting at the current case
+    let mut rest = solver.add_variable();
+    solver.add_clause(&[!rest]);
+    for (condition, value) in function.cases().iter().rev() {
+        // `applies` is true iff every literal of the condition holds
+        let applies = solver.add_variable();
+        let mut clause = vec![applies];
+        for &lit in condition {
+            let lit = solver.lookup(lit);
+            solver.add_clause(&[!applies, lit]);
+            clause.push(!lit);
+        }
+        solver.add_clause(&clause);
+
+        let chain = solver.add_variable();
+        if *value {
+            // chain <-> applies | rest
+            solver.add_clause(&[!chain, applies, rest]);
+            solver.add_clause(&[chain, !applies]);
+            solver.add_clause(&[chain, !rest]);
+        } else {
+            // chain <-> !applies & rest
+            solver.add_clause(&[!chain, !applies]);
+            solver.add_clause(&[!chain, rest]);
+            solver.add_clause(&[chain, applies, !rest]);
+        }
+        rest = chain;
+    }
+    let var = solver.lookup(function.var().positive());
+    solver.add_clause(&[!var, rest]);
+    solver.add_clause(&[var, !rest]);
+}
+
+/// Returns whether the dependency graph contains a cycle, using Kahn's algorithm.
+fn has_cycle(dependencies: &HashMap<Var, HashSet<Var>>) -> bool {
+    let mut remaining: HashMap<Var, usize> =
+        dependencies.iter().map(|(&var, deps)| (var, deps.len())).collect();
+    let mut dependents: HashMap<Var, Vec<Var>> = HashMap::new();
+    for (&var, deps) in dependencies {
+        for &dep in deps {
+            dependents.entry(dep).or_default().push(var);
+            remaining.entry(dep).or_insert(0);
+        }
+    }
+    let mut ready: Vec<Var> =
+        remaining.iter().filter(|&(_, &count)| count == 0).map(|(&var, _)| var).collect();
+    let mut processed = 0;
+    while let Some(var) = ready.pop() {
+        processed += 1;
+        for &dependent in dependents.get(&var).into_iter().flatten() {
+            let count = remaining.get_mut(&dependent).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push(dependent);
+            }
+        }
+    }
+    processed < remaining.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{incdet::IncDet, literal::Lit, SolverResult};
+    use proptest::prelude::*;
+
+    fn lits(lits: &[i32]) -> Vec<Lit> {
+        lits.iter().map(|&lit| Lit::from_dimacs(lit)).collect()
+    }
+
+    fn function(var: i32, cases: &[(&[i32], bool)]) -> SkolemFn {
+        let cases = cases.iter().map(|&(condition, value)| (lits(condition), value)).collect();
+        SkolemFn::new(Var::from_dimacs(var), cases)
+    }
+
+    #[test]
+    fn valid_certificate() {
+        let qcnf = qcnf_formula![
+            a 1 2;
+            e 3 4;
+            // 3 <=> 1 & 2
+            1 -3; 2 -3; -1 -2 3;
+            // 4 <=> 1 | 3
+            -1 4; -3 4; 1 3 -4;
+        ];
+        let cert: SkolemCertificate = [
+            function(3, &[(&[1, 2], true)]),
+            // 4 depends on the existential 3
+            function(4, &[(&[1], true), (&[3], true)]),
+        ]
+        .into_iter()
+        .collect();
+        assert!(verify_skolem(&qcnf, &cert));
+
+        // the certificate of the solver
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), SolverResult::Satisfiable);
+        let cert = [3, 4].map(|var| solver.model_function(Var::from_dimacs(var))).into();
+        assert!(verify_skolem(&qcnf, &SkolemCertificate::new(cert)));
+    }
+
+    #[test]
+    fn invalid_certificate() {
+        let qcnf = qcnf_formula![
+            a 1 2;
+            e 3;
+            1 -3; 2 -3; -1 -2 3;
+        ];
+        // 3 <=> 1 | 2 is wrong
+        let cert = SkolemCertificate::new(vec![function(3, &[(&[1], true), (&[2], true)])]);
+        assert!(!verify_skolem(&qcnf, &cert));
+        // missing function
+        assert!(!verify_skolem(&qcnf, &SkolemCertificate::default()));
+        // dependency on a variable that is not part of the formula
+        let cert = SkolemCertificate::new(vec![function(3, &[(&[1, 2, 5], true)])]);
+        assert!(!verify_skolem(&qcnf, &cert));
+        // function for a universal variable
+        let cert = SkolemCertificate::new(vec![function(3, &[(&[1, 2], true)]), function(1, &[])]);
+        assert!(!verify_skolem(&qcnf, &cert));
+    }
+
+    #[test]
+    fn dependencies() {
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            e 3 4;
+            -2 1;
+            3 4;
+        ];
+        // 1 must not depend on the universal 2 bound after it
+        let cert = SkolemCertificate::new(vec![
+            function(1, &[(&[2], true)]),
+            function(3, &[(&[], true)]),
+            function(4, &[]),
+        ]);
+        assert!(!verify_skolem(&qcnf, &cert));
+        let cert = SkolemCertificate::new(vec![
+            function(1, &[(&[], true)]),
+            function(3, &[(&[], true)]),
+            function(4, &[]),
+        ]);
+        assert!(verify_skolem(&qcnf, &cert));
+        // functions may depend on variables that do not occur in the formula
+        let cert = SkolemCertificate::new(vec![
+            function(1, &[(&[], true)]),
+            function(3, &[(&[9], true)]),
+            function(4, &[]),
+            function(9, &[(&[], true)]),
+        ]);
+        assert!(verify_skolem(&qcnf, &cert));
+        // cyclic dependencies are rejected, even if the cycle is consistent
+        let cert = SkolemCertificate::new(vec![
+            function(1, &[(&[], true)]),
+            function(3, &[(&[-4], true)]),
+            function(4, &[(&[-3], true)]),
+        ]);
+        assert!(!verify_skolem(&qcnf, &cert));
+    }
+
+    #[test]
+    fn negative_free_variable() {
+        // the free variable 4 only occurs negatively
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2;
+            1 2 -4;
+            -1 -2;
+        ];
+        let cert = SkolemCertificate::new(vec![function(2, &[(&[-1], true)]), function(4, &[])]);
+        assert!(verify_skolem(&qcnf, &cert));
+        let cert = SkolemCertificate::new(vec![function(2, &[]), function(4, &[(&[], true)])]);
+        assert!(!verify_skolem(&qcnf, &cert));
+    }
+
+    proptest! {
+        #[test]
+        fn solver_certificates_are_valid(qcnf in crate::qcnf::strategy::qcnf(2..=2, 3..6, 8..16, 2..4)) {
+            let mut solver = IncDet::from_qcnf(&qcnf);
+            if solver.solve() == SolverResult::Satisfiable {
+                let cert = qcnf
+                    .prefix
+                    .iter()
+                    .filter(|(quant, _)| *quant == QuantTy::Exists)
+                    .flat_map(|(_, vars)| vars.iter().map(|&var| solver.model_function(var)))
+                    .collect();
+                prop_assert!(verify_skolem(&qcnf, &cert), "{qcnf}");
+            }
+        }
+
+        #[test]
+        fn unsat_formulas_have_no_certificate(
+            qcnf in crate::qcnf::strategy::qcnf(2..=2, 1..6, 0..16, 1..4),
+            values in proptest::collection::vec(any::<bool>(), 6),
+        ) {
+            let mut solver = IncDet::from_qcnf(&qcnf);
+            if solver.solve() == SolverResult::Unsatisfiable {
+                // constant functions as well as the (partial) functions of the solver are rejected
+                let existentials: Vec<Var> = qcnf
+                    .prefix
+                    .iter()
+                    .filter(|(quant, _)| *quant == QuantTy::Exists)
+                    .flat_map(|(_, vars)| vars.iter().copied())
+                    .collect();
+                let constant = existentials
+                    .iter()
+                    .zip(&values)
+                    .map(|(&var, &value)| SkolemFn::new(var, vec![(Vec::new(), value)]))
+                    .collect();
+                prop_assert!(!verify_skolem(&qcnf, &constant), "{qcnf}");
+                let cert =
+                    existentials.iter().map(|&var| solver.model_function(var)).collect();
+                prop_assert!(!verify_skolem(&qcnf, &cert), "{qcnf}");
+            }
+        }
+    }
+}
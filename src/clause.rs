@@ -1,6 +1,8 @@
-use std::collections::HashSet;
-
-use crate::literal::{filter_lit, Lit};
+use crate::{
+    datastructure::LitSet,
+    literal::{filter_lit, Lit},
+};
+use ::alloc::vec::Vec;
 
 pub(crate) mod alloc;
 
@@ -10,12 +12,36 @@ pub struct Clause {
 }
 
 impl Clause {
+    /// Creates a clause of arbitrary size, including the empty clause, see the sized constructors below.
     pub(crate) fn new(literals: &[Lit]) -> Self {
-        // assert!(literals.len() > 2);
         Self { lits: literals.to_vec() }
     }
 
-    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Lit> {
+    /// Creates a clause with a single literal.
+    pub(crate) fn new_unit(lit: Lit) -> Self {
+        Self::new(&[lit])
+    }
+
+    /// Creates a clause with two literals.
+    pub(crate) fn new_binary(lits: [Lit; 2]) -> Self {
+        Self::new(&lits)
+    }
+
+    /// Creates a clause with more than two literals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are at most two literals, use [`Clause::new_unit`] or [`Clause::new_binary`] instead.
+    pub(crate) fn new_long(literals: &[Lit]) -> Self {
+        assert!(
+            literals.len() > 2,
+            "long clauses have more than two literals, got {}",
+            literals.len()
+        );
+        Self::new(literals)
+    }
+
+    pub(crate) fn iter(&self) -> core::slice::Iter<'_, Lit> {
         self.lits.iter()
     }
 
@@ -28,17 +54,57 @@ impl Clause {
         &mut self.lits
     }
 
-    /// For a clause of the form $\bigwedge_{p \in premise} \rightarrow implied_lit$,
+    /// For a clause of the form `$\bigwedge_{p \in premise} \rightarrow implied_lit$`,
     /// this function returns whether the premise is satisfied by the assignment.
     /// As a consequence, the `implied_lit` has to be true.
-    pub(crate) fn is_implied(&self, implied_lit: Lit, assignment: &HashSet<Lit>) -> bool {
+    pub(crate) fn is_implied(&self, implied_lit: Lit, assignment: &LitSet) -> bool {
         assert!(self.lits.contains(&implied_lit));
-        !self.iter().filter(filter_lit(implied_lit)).any(|l| assignment.contains(l))
+        !self.iter().filter(filter_lit(implied_lit)).any(|&l| assignment.contains(l))
+    }
+
+    /// Returns whether the clause contains a literal and its negation.
+    pub(crate) fn is_tautology(&self) -> bool {
+        is_tautology(&self.lits)
     }
+
+    /// Returns a hash of the literals that is independent of their order, i.e., clauses that are
+    /// equal up to permutation share a signature.
+    /// Different signatures imply different clauses, the converse does not hold.
+    pub(crate) fn signature(&self) -> u64 {
+        signature(&self.lits)
+    }
+
+    /// Returns whether every literal of this clause is contained in `other`.
+    pub(crate) fn subsumes(&self, other: &Clause) -> bool {
+        self.lits.len() <= other.lits.len() && self.iter().all(|lit| other.lits.contains(lit))
+    }
+}
+
+/// Returns whether `lits` contain a literal and its negation, the literals do not need to be sorted.
+pub(crate) fn is_tautology(lits: &[Lit]) -> bool {
+    if lits.windows(2).all(|pair| pair[0] <= pair[1]) {
+        // literals are sorted by variable, thus, literals of opposing signs are adjacent
+        lits.windows(2).any(|pair| pair[0] == !pair[1])
+    } else {
+        lits.iter().any(|&lit| lits.contains(&!lit))
+    }
+}
+
+/// Returns the order independent hash of `lits`, see [`Clause::signature`].
+fn signature(lits: &[Lit]) -> u64 {
+    lits.iter().fold(0, |signature, lit| signature.wrapping_add(mix(lit.as_index() as u64)))
+}
+
+/// Finalizer of splitmix64, spreads the bits of `value` over the result.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
-impl std::fmt::Display for Clause {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Clause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for &lit in &self.lits {
             write!(f, "{lit} ")?;
         }
@@ -48,9 +114,62 @@ impl std::fmt::Display for Clause {
 
 impl<'a> IntoIterator for &'a Clause {
     type Item = &'a Lit;
-    type IntoIter = std::slice::Iter<'a, Lit>;
+    type IntoIter = core::slice::Iter<'a, Lit>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn clause(lits: &[i32]) -> Clause {
+        Clause::new(&lits.iter().map(|&lit| Lit::from_dimacs(lit)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn signature() {
+        assert_eq!(clause(&[1, -2, 3]).signature(), clause(&[3, 1, -2]).signature());
+        assert_eq!(clause(&[1, -2, 3]).signature(), clause(&[-2, 3, 1]).signature());
+        assert_ne!(clause(&[1, -2, 3]).signature(), clause(&[1, 2, 3]).signature());
+        assert_ne!(clause(&[1, -2]).signature(), clause(&[1, -2, 3]).signature());
+    }
+
+    #[test]
+    fn sized_constructors() {
+        let lits: Vec<Lit> = [1, -2, 3].iter().map(|&lit| Lit::from_dimacs(lit)).collect();
+        assert_eq!(Clause::new_unit(lits[0]), clause(&[1]));
+        assert_eq!(Clause::new_binary([lits[0], lits[1]]), clause(&[1, -2]));
+        assert_eq!(Clause::new_long(&lits), clause(&[1, -2, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than two literals")]
+    fn long_clause_too_short() {
+        let _ = Clause::new_long(&[Lit::from_dimacs(1), Lit::from_dimacs(2)]);
+    }
+
+    #[test]
+    fn is_tautology() {
+        assert!(clause(&[1, -1]).is_tautology());
+        assert!(clause(&[-2, 1, 3, 2]).is_tautology());
+        // opposing literals that are not adjacent after sorting by the first literals
+        assert!(clause(&[2, 1, -2]).is_tautology());
+        assert!(!clause(&[1, -2, 3]).is_tautology());
+        assert!(!clause(&[3, 1, 1]).is_tautology());
+        assert!(!clause(&[]).is_tautology());
+    }
+
+    #[test]
+    fn subsumes() {
+        let small = clause(&[1, -2]);
+        let large = clause(&[3, -2, 4, 1]);
+        assert!(small.subsumes(&large));
+        assert!(!large.subsumes(&small));
+        assert!(small.subsumes(&clause(&[-2, 1])));
+        assert!(!small.subsumes(&clause(&[1, 2, 3])));
+        assert!(clause(&[]).subsumes(&small));
+    }
+}
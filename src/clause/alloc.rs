@@ -2,13 +2,22 @@
 
 use super::Clause;
 use crate::literal::Lit;
+use alloc::vec::Vec;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct ClauseId(usize);
+pub(crate) struct ClauseId(u32);
+
+impl ClauseId {
+    pub(crate) fn as_index(self) -> usize {
+        usize::try_from(self.0).unwrap()
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Allocator {
     clauses: Vec<Clause>,
+    /// the total number of literals of the stored clauses
+    num_lits: usize,
 }
 
 impl Allocator {
@@ -16,29 +25,75 @@ impl Allocator {
         self.clauses.reserve(usize::try_from(num_clauses).unwrap());
     }
 
-    #[allow(unused)]
+    /// Returns the number of clauses in the allocator.
     pub(crate) fn len(&self) -> usize {
         self.clauses.len()
     }
 
+    /// Removes all clauses while keeping the allocated capacity.
+    pub(crate) fn clear(&mut self) {
+        self.clauses.clear();
+        self.num_lits = 0;
+    }
+
+    /// Returns the approximate number of bytes used by the stored clauses.
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.clauses.capacity() * core::mem::size_of::<Clause>()
+            + self.num_lits * core::mem::size_of::<Lit>()
+    }
+
+    /// Iterates over all clauses in insertion order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (ClauseId, &Clause)> {
+        (0..).map(ClauseId).zip(&self.clauses)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the allocator already stores `u32::MAX` clauses.
     pub(crate) fn add(&mut self, clause: &[Lit]) -> ClauseId {
-        let clause = Clause::new(clause);
-        let idx = self.clauses.len();
+        let clause = match *clause {
+            [] => Clause::new(clause),
+            [lit] => Clause::new_unit(lit),
+            [first, second] => Clause::new_binary([first, second]),
+            _ => Clause::new_long(clause),
+        };
+        let idx = u32::try_from(self.clauses.len()).expect("number of clauses exceeds u32::MAX");
+        self.num_lits += clause.lits().len();
         self.clauses.push(clause);
         ClauseId(idx)
     }
 }
 
-impl std::ops::Index<ClauseId> for Allocator {
+impl core::ops::Index<ClauseId> for Allocator {
     type Output = Clause;
 
     fn index(&self, index: ClauseId) -> &Self::Output {
-        &self.clauses[index.0]
+        &self.clauses[index.as_index()]
     }
 }
 
-impl std::ops::IndexMut<ClauseId> for Allocator {
+impl core::ops::IndexMut<ClauseId> for Allocator {
     fn index_mut(&mut self, index: ClauseId) -> &mut Self::Output {
-        &mut self.clauses[index.0]
+        &mut self.clauses[index.as_index()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iter() {
+        let mut allocator = Allocator::default();
+        let clauses = [
+            vec![Lit::from_dimacs(1), Lit::from_dimacs(-2)],
+            vec![Lit::from_dimacs(2)],
+            vec![Lit::from_dimacs(-1), Lit::from_dimacs(3)],
+        ];
+        let ids: Vec<_> = clauses.iter().map(|clause| allocator.add(clause)).collect();
+        assert_eq!(allocator.len(), 3);
+        let iterated: Vec<_> =
+            allocator.iter().map(|(id, clause)| (id, clause.lits().to_vec())).collect();
+        assert_eq!(iterated, ids.into_iter().zip(clauses).collect::<Vec<_>>());
     }
 }
@@ -1,7 +1,27 @@
-use miette::{Diagnostic, Result};
-use std::{env::args, io::Read, path::PathBuf};
+use crate::{
+    incdet::IncDet,
+    literal::{Lit, Var},
+    qcnf::QCNF,
+    qdimacs::{ExtendedParseError, FromQdimacs, QdimacsParser},
+    QuantTy, SolverResult,
+};
+use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, Result};
+use std::{
+    env::args,
+    io::{Cursor, Read},
+    path::PathBuf,
+};
 use thiserror::Error;
 
+mod batch;
+mod check;
+
+pub use batch::{solve_dir, BatchOptions, QDIMACS_EXTENSION};
+pub use check::{check, CheckReport, Issue};
+
+/// The flag of the `booleanium` binary that validates the input instead of solving it, see [`check_from_args`].
+pub const CHECK_FLAG: &str = "--check";
+
 #[derive(Debug, Error, Diagnostic)]
 pub enum ArgError {
     #[error("Expect single argument containing the path to a QDIMACS file")]
@@ -13,34 +33,275 @@ pub enum ArgError {
     #[error("{} is not a file", path.display())]
     NotAFile { path: PathBuf },
 
+    #[error("{} is not a directory", path.display())]
+    NotADirectory { path: PathBuf },
+
     #[error("Cannot read file {}: {}", path.display(), err)]
     CannotReadFile { path: PathBuf, err: std::io::Error },
 
+    #[error("Cannot read directory {}: {}", path.display(), err)]
+    CannotReadDir { path: PathBuf, err: std::io::Error },
+
     #[error("Cannot read from stdin: {}", err)]
     CannotReadStdIn { err: std::io::Error },
 }
 
+/// Reads the input file given as single command line argument, or stdin if no argument is given.
+///
+/// # Errors
+///
+/// Returns an [`ArgError`] if the arguments are invalid or the input cannot be read.
 pub fn content_from_args() -> Result<Vec<u8>> {
-    let mut args = args();
-    if args.len() == 1 {
+    content_from(path_from_args()?)
+}
+
+fn content_from(file_path: Option<PathBuf>) -> Result<Vec<u8>> {
+    let Some(file_path) = file_path else {
         tracing::info!("No arguments provided, read from stdin");
         let mut buffer = Vec::new();
         std::io::stdin()
             .read_to_end(&mut buffer)
             .map_err(|err| ArgError::CannotReadStdIn { err })?;
         return Ok(buffer);
-    } else if args.len() != 2 {
+    };
+    let contents = std::fs::read(&file_path)
+        .map_err(|err| ArgError::CannotReadFile { path: file_path.clone(), err })?;
+    Ok(contents)
+}
+
+/// Returns the path to the input file given as single command line argument,
+/// or `None` if no argument is given.
+///
+/// # Errors
+///
+/// Returns an [`ArgError`] if the arguments are invalid or the path is not a file.
+pub fn path_from_args() -> Result<Option<PathBuf>> {
+    path_from(args().skip(1))
+}
+
+fn path_from(mut args: impl ExactSizeIterator<Item = String>) -> Result<Option<PathBuf>> {
+    if args.len() == 0 {
+        return Ok(None);
+    } else if args.len() != 1 {
         return Err(ArgError::ExpectedFile.into());
     }
-    let file_path = args.nth(1).unwrap();
-    let file_path = PathBuf::from(file_path);
+    let file_path = PathBuf::from(args.next().unwrap());
     if !file_path.exists() {
         return Err(ArgError::FileDoesNotExist { path: file_path }.into());
     }
     if !file_path.is_file() {
         return Err(ArgError::NotAFile { path: file_path }.into());
     }
-    let contents = std::fs::read(&file_path)
-        .map_err(|err| ArgError::CannotReadFile { path: file_path.clone(), err })?;
-    Ok(contents)
+    Ok(Some(file_path))
+}
+
+/// Installs a graphical report handler, such that errors are rendered with their source snippet and labels.
+/// Colors are disabled if the `NO_COLOR` environment variable is set to a non-empty value.
+///
+/// # Errors
+///
+/// Returns an error if a report handler has already been installed.
+pub fn install_report_handler() -> Result<()> {
+    let color = std::env::var_os("NO_COLOR").map_or(true, |value| value.is_empty());
+    miette::set_hook(Box::new(move |_| {
+        let theme =
+            if color { GraphicalTheme::unicode() } else { GraphicalTheme::unicode_nocolor() };
+        Box::new(GraphicalReportHandler::new_themed(theme))
+    }))?;
+    Ok(())
+}
+
+/// Runs the solver like the `booleanium` binary: the formula is parsed from the file given as single
+/// command line argument, or stdin if no argument is given, solved, and the result status is printed.
+/// Free variables are existentially quantified (see [`QCNF::lift_from_cnf`]), thus, plain DIMACS files are solved as SAT.
+///
+/// The returned value can be returned from `main`, its exit code is the one of the [`SolverResult`].
+/// Logging and the report handler (see [`install_report_handler`]) are left to the caller.
+///
+/// # Example
+/// ```no_run
+/// fn main() -> impl std::process::Termination {
+///     booleanium::run_on_stdin()
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`ArgError`] if the input cannot be read, or the parse error rendered with the input.
+pub fn run_on_stdin() -> Result<SolverResult> {
+    let mut solver = parse_from_args::<Lifted>()?.into_solver();
+
+    let result = solver.solve();
+    println!("result status: {result}");
+
+    Ok(result)
+}
+
+/// Validates the input like `booleanium --check [file]` without solving it: the formula is read from the file
+/// given after [`CHECK_FLAG`], or stdin if no file is given, and the issues found by [`check()`] are printed
+/// with their source spans. Prints a single line if there are no issues.
+///
+/// # Errors
+///
+/// Returns an [`ArgError`] if the arguments are invalid or the input cannot be read,
+/// the parse error rendered with the input, or the [`CheckReport`] if an issue is an error.
+/// Issues that are only warnings are printed to stderr.
+pub fn check_from_args() -> Result<()> {
+    let mut args = args().skip(1);
+    if args.next().as_deref() != Some(CHECK_FLAG) {
+        return Err(ArgError::ExpectedFile.into());
+    }
+    let contents = content_from(path_from(args)?)?;
+    let issues = match check(&contents) {
+        Ok(issues) => issues,
+        Err(err) => {
+            return Err(ExtendedParseError { source_code: contents, related: vec![err] }.into())
+        }
+    };
+    if issues.is_empty() {
+        println!("no issues found");
+        return Ok(());
+    }
+    let report = CheckReport { source_code: contents, issues };
+    if report.has_errors() {
+        return Err(report.into());
+    }
+    eprintln!("{:?}", miette::Report::new(report));
+    Ok(())
+}
+
+/// The formula parsed by [`run_on_stdin`], free variables are bound while the clauses are added to the solver.
+/// Only if a free variable occurs and the outermost scope is universal, the formula is collected and lifted
+/// as [`QCNF`], as the solver cannot add a scope before the existing ones.
+#[derive(Debug)]
+enum Lifted {
+    Solver(IncDet),
+    Formula(QCNF),
+}
+
+impl Default for Lifted {
+    fn default() -> Self {
+        Self::Solver(IncDet::default())
+    }
+}
+
+impl Lifted {
+    fn into_solver(self) -> IncDet {
+        match self {
+            Self::Solver(solver) => solver,
+            Self::Formula(mut qcnf) => {
+                qcnf.lift_from_cnf();
+                IncDet::from_qcnf(&qcnf)
+            }
+        }
+    }
+}
+
+impl FromQdimacs for Lifted {
+    fn set_num_variables(&mut self, variables: u32) {
+        match self {
+            Self::Solver(solver) => solver.set_num_variables(variables),
+            Self::Formula(qcnf) => qcnf.set_num_variables(variables),
+        }
+    }
+
+    fn set_num_clauses(&mut self, clauses: u32) {
+        match self {
+            Self::Solver(solver) => solver.set_num_clauses(clauses),
+            Self::Formula(qcnf) => qcnf.set_num_clauses(clauses),
+        }
+    }
+
+    fn quantify(&mut self, quant: QuantTy, vars: &[Var]) {
+        match self {
+            Self::Solver(solver) => solver.quantify(quant, vars),
+            Self::Formula(qcnf) => qcnf.quantify(quant, vars),
+        }
+    }
+
+    fn add_clause(&mut self, lits: &[Lit]) {
+        if let Self::Solver(solver) = self {
+            if solver.bind_free_variables(lits) {
+                solver.add_clause(lits);
+                return;
+            }
+            // the clauses added so far contain no free variables, their universal reduction is unaffected
+            *self = Self::Formula(solver.to_qcnf());
+        }
+        if let Self::Formula(qcnf) = self {
+            qcnf.add_clause(lits);
+        }
+    }
+}
+
+#[cfg(not(feature = "mmap"))]
+fn parse_from_args<T: FromQdimacs>() -> Result<T> {
+    let contents = content_from_args()?;
+    let reader = Cursor::new(&contents);
+
+    match QdimacsParser::new(reader).parse() {
+        Ok(q) => Ok(q),
+        Err(err) => Err(ExtendedParseError { source_code: contents, related: vec![err] })?,
+    }
+}
+
+#[cfg(feature = "mmap")]
+fn parse_from_args<T: FromQdimacs>() -> Result<T> {
+    let Some(path) = path_from_args()? else {
+        let contents = content_from_args()?;
+        return match QdimacsParser::new(Cursor::new(&contents)).parse() {
+            Ok(q) => Ok(q),
+            Err(err) => Err(ExtendedParseError { source_code: contents, related: vec![err] })?,
+        };
+    };
+    match QdimacsParser::from_path(&path).and_then(|mut parser| parser.parse()) {
+        Ok(q) => Ok(q),
+        Err(err) => {
+            // the file content is only copied for error reporting
+            let contents = content_from_args()?;
+            Err(ExtendedParseError { source_code: contents, related: vec![err] })?
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::qdimacs::ParseError;
+
+    fn parse(input: &str) -> Result<Lifted, ParseError> {
+        QdimacsParser::new(Cursor::new(input)).parse()
+    }
+
+    #[test]
+    fn lifted() -> Result<(), ParseError> {
+        // plain DIMACS is solved as SAT without the intermediate formula
+        let lifted = parse("p cnf 3 4\n1 2 0\n-1 3 0\n-2 -3 0\n-3 2 0\n")?;
+        assert!(matches!(lifted, Lifted::Solver(_)));
+        let mut solver = lifted.into_solver();
+        assert_eq!(solver.prefix().count(), 1);
+        assert_eq!(solver.solve(), SolverResult::Satisfiable);
+        assert_eq!(
+            parse("p cnf 1 2\n1 0\n-1 0\n")?.into_solver().solve(),
+            SolverResult::Unsatisfiable
+        );
+
+        // free variables are added to an outermost existential scope
+        let lifted = parse("p cnf 3 2\ne 1 0\na 2 0\n1 2 3 0\n-2 -3 0\n")?;
+        assert!(matches!(lifted, Lifted::Solver(_)));
+        let mut solver = lifted.into_solver();
+        assert_eq!(solver.to_qcnf().prefix.len(), 2);
+        assert_eq!(solver.solve(), SolverResult::Satisfiable);
+
+        // before an outermost universal scope, the formula is lifted
+        let input = "p cnf 3 2\na 1 0\ne 2 0\n1 2 0\n-1 -2 3 0\n";
+        let lifted = parse(input)?;
+        assert!(matches!(lifted, Lifted::Formula(_)));
+        let mut qcnf: QCNF = QdimacsParser::new(Cursor::new(input)).parse()?;
+        qcnf.lift_from_cnf();
+        let mut solver = lifted.into_solver();
+        assert!(solver.to_qcnf().semantically_eq(&qcnf));
+        assert_eq!(solver.solve(), IncDet::from_qcnf(&qcnf).solve());
+        Ok(())
+    }
 }
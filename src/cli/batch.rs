@@ -0,0 +1,160 @@
+//! Solving every QDIMACS file of a directory, see [`solve_dir`].
+
+use super::ArgError;
+use crate::{incdet::IncDet, qcnf::QCNF, qdimacs::QdimacsParser, SolverResult};
+use miette::Result;
+use std::{
+    fs,
+    io::Cursor,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// The file extension of the instances solved by [`solve_dir`].
+pub const QDIMACS_EXTENSION: &str = "qdimacs";
+
+/// The options of [`solve_dir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchOptions {
+    /// the wall-clock time after which solving a single file is interrupted, `None` means unlimited
+    pub timeout: Option<Duration>,
+}
+
+/// Solves every file with extension [`QDIMACS_EXTENSION`] in the directory `path` and its subdirectories,
+/// one after another, and returns the result and solving time of every file, sorted by path.
+///
+/// Like [`run_on_stdin`](super::run_on_stdin), free variables are existentially quantified.
+/// Files that cannot be read or parsed, that exceed the timeout, or that make the solver panic
+/// result in [`SolverResult::Unknown`], the reason is logged. Panics are only caught if the
+/// crate is built with `panic = "unwind"`, which is not the case for the release profile.
+///
+/// # Errors
+///
+/// Returns an [`ArgError`] if `path` is not a directory or a directory cannot be read.
+pub fn solve_dir(
+    path: &Path,
+    options: &BatchOptions,
+) -> Result<Vec<(PathBuf, SolverResult, Duration)>> {
+    if !path.is_dir() {
+        return Err(ArgError::NotADirectory { path: path.to_owned() }.into());
+    }
+    let mut files = Vec::new();
+    collect_files(path, &mut files)?;
+    files.sort();
+
+    let results = files
+        .into_iter()
+        .map(|file| {
+            let start = Instant::now();
+            let result = solve_file(&file, options);
+            let elapsed = start.elapsed();
+            info!("{}: {result} in {elapsed:?}", file.display());
+            (file, result, elapsed)
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Adds the instances in `dir` and its subdirectories to `files`.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| ArgError::CannotReadDir { path: dir.to_owned(), err })?;
+    for entry in entries {
+        let path =
+            entry.map_err(|err| ArgError::CannotReadDir { path: dir.to_owned(), err })?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == QDIMACS_EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn solve_file(file: &Path, options: &BatchOptions) -> SolverResult {
+    let contents = match fs::read(file) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("cannot read file {}: {err}", file.display());
+            return SolverResult::Unknown;
+        }
+    };
+    let mut qcnf: QCNF = match QdimacsParser::new(Cursor::new(&contents)).parse() {
+        Ok(qcnf) => qcnf,
+        Err(err) => {
+            warn!("cannot parse file {}: {err}", file.display());
+            return SolverResult::Unknown;
+        }
+    };
+    qcnf.lift_from_cnf();
+
+    let interrupt = Arc::new(AtomicBool::new(false));
+    // the timer stops early once `finished` is dropped
+    let (finished, timer) = mpsc::channel::<()>();
+    if let Some(timeout) = options.timeout {
+        let interrupt = Arc::clone(&interrupt);
+        thread::spawn(move || {
+            if timer.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                interrupt.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        solver.set_interrupt(Arc::clone(&interrupt));
+        solver.solve()
+    }));
+    drop(finished);
+
+    let Ok(result) = result else {
+        warn!("solver panicked on {}", file.display());
+        return SolverResult::Unknown;
+    };
+    if result == SolverResult::Unknown && interrupt.load(Ordering::Relaxed) {
+        warn!("solving {} exceeded the timeout", file.display());
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solve_dir() {
+        let dir = std::env::temp_dir().join(format!("booleanium-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("sat.qdimacs"), "p cnf 2 1\na 1 0\ne 2 0\n1 2 0\n").unwrap();
+        fs::write(dir.join("nested/unsat.qdimacs"), "p cnf 2 2\na 1 0\ne 2 0\n1 2 0\n1 -2 0\n")
+            .unwrap();
+        fs::write(dir.join("invalid.qdimacs"), "p cnf 1 2\n1 0\n").unwrap();
+        fs::write(dir.join("ignored.txt"), "p cnf 1 2\n1 0\n").unwrap();
+
+        let options = BatchOptions { timeout: Some(Duration::from_secs(60)) };
+        let results = super::solve_dir(&dir, &options).unwrap();
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|(path, result, _)| (path.strip_prefix(&dir).unwrap().to_owned(), result))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (PathBuf::from("invalid.qdimacs"), SolverResult::Unknown),
+                (PathBuf::from("nested/unsat.qdimacs"), SolverResult::Unsatisfiable),
+                (PathBuf::from("sat.qdimacs"), SolverResult::Satisfiable),
+            ]
+        );
+
+        assert!(super::solve_dir(&dir.join("sat.qdimacs"), &options).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
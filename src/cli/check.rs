@@ -0,0 +1,237 @@
+//! Structural validation of QDIMACS inputs without solving them, see [`check`].
+
+use crate::{
+    literal::{Lit, Var},
+    qdimacs::{FromQdimacs, ParseError, QdimacsParser},
+    QuantTy,
+};
+use miette::{Diagnostic, Severity, SourceSpan};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+};
+use thiserror::Error;
+
+/// A structural problem of a syntactically valid QDIMACS input.
+#[derive(Debug, Error, Diagnostic)]
+pub enum Issue {
+    #[error("Variable {var} is quantified more than once")]
+    #[diagnostic(help("every variable may be bound by at most one quantifier"))]
+    DuplicateQuantification {
+        var: Var,
+
+        #[label("first bound here")]
+        first_span: SourceSpan,
+
+        #[label("bound again here")]
+        err_span: SourceSpan,
+    },
+
+    #[error("Variable {var} is not bound by the prefix")]
+    #[diagnostic(
+        severity(Warning),
+        help("free variables are existentially quantified in the outermost scope")
+    )]
+    FreeVariable {
+        var: Var,
+
+        #[label("first occurrence")]
+        err_span: SourceSpan,
+    },
+
+    #[error("Universal literal {lit} is removed by universal reduction")]
+    #[diagnostic(
+        severity(Warning),
+        help("no existential variable of the clause is quantified after the universal variable")
+    )]
+    ReducibleLiteral {
+        lit: Lit,
+
+        #[label]
+        err_span: SourceSpan,
+    },
+}
+
+/// The issues found by [`check`], rendered with the input.
+#[derive(Debug, Error, Diagnostic)]
+#[error("QDIMACS validation found {} issue(s)", issues.len())]
+pub struct CheckReport {
+    #[source_code]
+    pub source_code: Vec<u8>,
+
+    #[related]
+    pub issues: Vec<Issue>,
+}
+
+impl CheckReport {
+    /// Returns whether an issue makes the input invalid, i.e., it is not only a warning.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity().unwrap_or(Severity::Error) == Severity::Error)
+    }
+}
+
+/// Parses the QDIMACS `contents` and validates the formula without solving it.
+///
+/// Syntax errors, including a clause count that does not match the header, are returned as [`ParseError`].
+/// Otherwise, the returned issues report variables that are quantified more than once,
+/// free variables, and universal literals removed by universal reduction, in the order of the input.
+///
+/// # Errors
+///
+/// Returns the error of the parser if `contents` is not valid QDIMACS.
+pub fn check(contents: &[u8]) -> Result<Vec<Issue>, ParseError> {
+    let formula: Validation =
+        QdimacsParser::new(Cursor::new(contents)).source_spans(true).parse()?;
+
+    let mut issues = Vec::new();
+    // the first binding of every variable as (scope index, span)
+    let mut bound: HashMap<Var, (usize, SourceSpan)> = HashMap::new();
+    let mut quantifiers = Vec::new();
+    for (scope, (quant, vars, spans)) in formula.prefix.iter().enumerate() {
+        quantifiers.push(*quant);
+        for (&var, &span) in vars.iter().zip(spans) {
+            if let Some(&(_, first_span)) = bound.get(&var) {
+                issues.push(Issue::DuplicateQuantification { var, first_span, err_span: span });
+            } else {
+                bound.insert(var, (scope, span));
+            }
+        }
+    }
+
+    let mut free = HashSet::new();
+    for (lits, spans) in &formula.matrix {
+        for (&lit, &span) in lits.iter().zip(spans) {
+            if !bound.contains_key(&lit.var()) && free.insert(lit.var()) {
+                issues.push(Issue::FreeVariable { var: lit.var(), err_span: span });
+            }
+        }
+        // free variables are existential in the outermost scope
+        let max_existential = lits
+            .iter()
+            .filter_map(|lit| match bound.get(&lit.var()) {
+                Some(&(scope, _)) if quantifiers[scope] == QuantTy::Exists => Some(scope),
+                Some(_) => None,
+                None => Some(0),
+            })
+            .max();
+        for (&lit, &span) in lits.iter().zip(spans) {
+            let Some(&(scope, _)) = bound.get(&lit.var()) else {
+                continue;
+            };
+            if quantifiers[scope] == QuantTy::Forall
+                && max_existential.map_or(true, |max| max < scope)
+            {
+                issues.push(Issue::ReducibleLiteral { lit, err_span: span });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// The scopes and clauses together with the spans of their variables and literals in the input.
+#[derive(Debug, Default)]
+struct Validation {
+    prefix: Vec<(QuantTy, Vec<Var>, Vec<SourceSpan>)>,
+    matrix: Vec<(Vec<Lit>, Vec<SourceSpan>)>,
+}
+
+impl FromQdimacs for Validation {
+    fn set_num_variables(&mut self, _variables: u32) {}
+
+    fn set_num_clauses(&mut self, clauses: u32) {
+        self.matrix.reserve(clauses.try_into().unwrap());
+    }
+
+    fn quantify(&mut self, _quant: QuantTy, _vars: &[Var]) {
+        unreachable!("scopes are reported with their source spans");
+    }
+
+    fn add_clause(&mut self, _lits: &[Lit]) {
+        unreachable!("clauses are reported with their source spans");
+    }
+
+    fn quantify_spanned(&mut self, quant: QuantTy, vars: &[Var], spans: &[SourceSpan]) {
+        self.prefix.push((quant, vars.to_vec(), spans.to_vec()));
+    }
+
+    fn add_clause_spanned(&mut self, lits: &[Lit], spans: &[SourceSpan]) {
+        self.matrix.push((lits.to_vec(), spans.to_vec()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spans(contents: &str) -> Vec<(String, &str)> {
+        check(contents.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|issue| {
+                let span = match &issue {
+                    Issue::DuplicateQuantification { err_span, .. }
+                    | Issue::FreeVariable { err_span, .. }
+                    | Issue::ReducibleLiteral { err_span, .. } => *err_span,
+                };
+                (issue.to_string(), &contents[span.offset()..span.offset() + span.len()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        assert!(check(b"p cnf 3 2\na 1 0\ne 2 3 0\n1 2 0\n-1 -2 3 0\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn duplicate_quantification() {
+        let issues = spans("p cnf 3 1\na 3 0\ne 1 2 0\na 2 0\n1 2 3 0\n");
+        assert_eq!(issues, [("Variable 2 is quantified more than once".to_owned(), "2")]);
+        let issues = check(b"p cnf 3 1\na 3 0\ne 1 2 0\na 2 0\n1 2 3 0\n").unwrap();
+        let Issue::DuplicateQuantification { first_span, err_span, .. } = issues[0] else {
+            panic!("expected duplicate quantification");
+        };
+        assert_eq!((first_span.offset(), err_span.offset()), (20, 26));
+    }
+
+    #[test]
+    fn free_variable() {
+        let issues = spans("p cnf 3 2\ne 1 0\n1 2 0 -2 3 0\n");
+        assert_eq!(
+            issues,
+            [
+                ("Variable 2 is not bound by the prefix".to_owned(), "2"),
+                ("Variable 3 is not bound by the prefix".to_owned(), "3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reducible_literal() {
+        let contents = "p cnf 3 3\ne 1 0\n\na 2 0\ne 3 0\n1 -2 0\n-2\n3 0\n2 0\n";
+        let issues = spans(contents);
+        assert_eq!(
+            issues,
+            [
+                ("Universal literal -2 is removed by universal reduction".to_owned(), "-2"),
+                ("Universal literal 2 is removed by universal reduction".to_owned(), "2"),
+            ]
+        );
+        let report = CheckReport {
+            source_code: contents.into(),
+            issues: check(contents.as_bytes()).unwrap(),
+        };
+        assert!(!report.has_errors());
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(matches!(
+            check(b"p cnf 2 2\ne 1 2 0\n1 2 0\n"),
+            Err(ParseError::NumClausesMismatch { expected: 2, found: 1, .. })
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::literal::{Lit, Var};
-use std::ops::{Index, IndexMut};
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
 
 pub(crate) mod heap;
 
@@ -32,10 +33,7 @@ impl<T> VarVec<Vec<T>> {
 
 impl<T> VarVec<T> {
     pub(crate) fn iter(&self) -> impl Iterator<Item = (Var, &T)> {
-        self.0
-            .iter()
-            .enumerate()
-            .map(|(idx, value)| (Var::from_index(idx.try_into().unwrap()), value))
+        Var::range(self.0.len()).zip(self.0.iter())
     }
 
     pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
@@ -84,6 +82,10 @@ impl<T> LitVec<Vec<T>> {
 }
 
 impl<T> LitVec<T> {
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Lit, &T)> {
+        self.0.iter().enumerate().map(|(idx, value)| (Lit::from_index(idx), value))
+    }
+
     pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
         self.0.iter_mut()
     }
@@ -102,3 +104,92 @@ impl<T> IndexMut<Lit> for LitVec<T> {
         &mut self.0[index.as_index()]
     }
 }
+
+/// A set of literals, represented as a bitset indexed by [`Lit`].
+/// Clearing the set keeps the allocated memory, such that it can be reused.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LitSet {
+    bits: Vec<u64>,
+}
+
+impl LitSet {
+    const BITS: usize = u64::BITS as usize;
+
+    pub(crate) fn set_var_count(&mut self, count: usize) {
+        self.bits.resize((count * 2 + Self::BITS - 1) / Self::BITS, 0);
+    }
+
+    /// Adds `lit` to the set and returns whether it was newly inserted.
+    pub(crate) fn insert(&mut self, lit: Lit) -> bool {
+        let (word, mask) = Self::position(lit);
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+        }
+        let inserted = self.bits[word] & mask == 0;
+        self.bits[word] |= mask;
+        inserted
+    }
+
+    pub(crate) fn contains(&self, lit: Lit) -> bool {
+        let (word, mask) = Self::position(lit);
+        self.bits.get(word).map_or(false, |bits| bits & mask != 0)
+    }
+
+    /// Iterates over the literals in the set, ordered by their index.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Lit> + '_ {
+        self.bits.iter().enumerate().flat_map(|(word, &bits)| {
+            (0..Self::BITS)
+                .filter(move |bit| bits & (1 << bit) != 0)
+                .map(move |bit| Lit::from_index(word * Self::BITS + bit))
+        })
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|bits| *bits = 0);
+    }
+
+    fn position(lit: Lit) -> (usize, u64) {
+        (lit.as_index() / Self::BITS, 1 << (lit.as_index() % Self::BITS))
+    }
+}
+
+impl Extend<Lit> for LitSet {
+    fn extend<I: IntoIterator<Item = Lit>>(&mut self, iter: I) {
+        for lit in iter {
+            self.insert(lit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::literal::strategy::lit;
+    use proptest::{collection, prelude::*};
+    use std::collections::HashSet;
+
+    proptest! {
+        #[test]
+        fn lit_set_matches_hash_set(
+            inserted in collection::vec(lit(0u32..150), 0..100),
+            queried in collection::vec(lit(0u32..200), 0..100),
+        ) {
+            let mut set = LitSet::default();
+            set.set_var_count(100);
+            let mut expected = HashSet::new();
+            for &lit in &inserted {
+                prop_assert_eq!(set.insert(lit), expected.insert(lit));
+            }
+            for lit in queried.iter().chain(&inserted) {
+                prop_assert_eq!(set.contains(*lit), expected.contains(lit));
+            }
+            let mut expected: Vec<_> = expected.into_iter().collect();
+            expected.sort_unstable();
+            prop_assert_eq!(set.iter().collect::<Vec<_>>(), expected);
+
+            set.clear();
+            prop_assert_eq!(set.iter().count(), 0);
+            prop_assert!(inserted.iter().all(|&lit| !set.contains(lit)));
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 use super::VarVec;
 use crate::literal::Var;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
 
 #[derive(Debug, Default, Clone)]
 pub(crate) struct VarHeap<T> {
@@ -51,7 +53,6 @@ where
         new_value
     }
 
-    #[allow(dead_code)]
     pub(crate) fn get_value(&self, var: Var) -> T {
         self.values[var]
     }
@@ -112,7 +113,7 @@ where
 			return;
 		};
         let parent_var = self.heap[parent];
-        if self.values[var] > self.values[parent_var] {
+        if self.is_greater(var, parent_var) {
             self.swap(pos, parent);
             self.sift_up(parent);
         }
@@ -121,16 +122,14 @@ where
     fn sift_down(&mut self, pos: usize) {
         let mut largest_idx = pos;
 
-        if let Some(left_idx) = self
-            .left(pos)
-            .filter(|&idx| self.values[self.heap[idx]] > self.values[self.heap[largest_idx]])
+        if let Some(left_idx) =
+            self.left(pos).filter(|&idx| self.is_greater(self.heap[idx], self.heap[largest_idx]))
         {
             largest_idx = left_idx;
         }
 
-        if let Some(right_idx) = self
-            .right(pos)
-            .filter(|&idx| self.values[self.heap[idx]] > self.values[self.heap[largest_idx]])
+        if let Some(right_idx) =
+            self.right(pos).filter(|&idx| self.is_greater(self.heap[idx], self.heap[largest_idx]))
         {
             largest_idx = right_idx;
         }
@@ -143,6 +142,11 @@ where
         }
     }
 
+    /// Compares variables by their value, ties are broken in favor of the smaller variable index.
+    fn is_greater(&self, a: Var, b: Var) -> bool {
+        (self.values[a], Reverse(a)) > (self.values[b], Reverse(b))
+    }
+
     fn swap(&mut self, a: usize, b: usize) {
         let var_a = self.heap[a];
         let var_b = self.heap[b];
@@ -176,7 +180,7 @@ where
 
 impl<T> VarHeap<T>
 where
-    T: Default + Copy + Ord + std::ops::MulAssign,
+    T: Default + Copy + Ord + core::ops::MulAssign,
 {
     /// Rescaling values does not change the relative order in the heap.
     pub(crate) fn rescale(&mut self, rescale_factor: T) {
@@ -194,7 +198,7 @@ mod test {
     fn heap() {
         let mut heap = VarHeap::<i32>::default();
         heap.set_var_count(4);
-        let vars: Vec<_> = (0..4).map(Var::from_index).collect();
+        let vars: Vec<_> = Var::range(4).collect();
         for &var in &vars {
             heap.add(var);
         }
@@ -210,4 +214,22 @@ mod test {
         heap.add(vars[1]);
         assert_eq!(heap.peek(), Some(vars[1]));
     }
+
+    #[test]
+    fn tie_break() {
+        let mut heap = VarHeap::<i32>::default();
+        heap.set_var_count(8);
+        let vars: Vec<_> = Var::range(8).collect();
+        for &var in vars.iter().rev() {
+            heap.add_and_set(var, 1);
+        }
+        assert_eq!(heap.peek(), Some(vars[0]));
+
+        heap.update_value(vars[5], |value| value + 1);
+        heap.update_value(vars[3], |value| value + 1);
+        assert_eq!(heap.pop(), Some(vars[3]));
+        assert_eq!(heap.pop(), Some(vars[5]));
+        assert_eq!(heap.pop(), Some(vars[0]));
+        assert_eq!(heap.pop(), Some(vars[1]));
+    }
 }
@@ -0,0 +1,171 @@
+//! Universal expansion for formulas with a single universal scope.
+//!
+//! The universal variables are eliminated by conjoining copies of the matrix,
+//! one for each universal assignment, where the existential variables bound
+//! after the universal scope are renamed in every copy.
+//! The resulting propositional formula is solved by a SAT solver.
+
+use crate::{
+    literal::Var,
+    qcnf::QCNF,
+    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    QuantTy, SolverResult,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tracing::{debug, error, warn};
+
+/// The maximal number of universal variables, i.e., there are at most `2^MAX_UNIVERSALS` copies.
+const MAX_UNIVERSALS: usize = 16;
+
+/// Solves a QBF with at most one universal scope by universal expansion.
+///
+/// Returns [`SolverResult::Unknown`] if the formula has more than one universal scope
+/// or the universal scope is too large to be expanded.
+#[must_use]
+pub fn solve_by_expansion(qcnf: &QCNF) -> SolverResult {
+    solve_by_expansion_with_interrupt(qcnf, &AtomicBool::new(false))
+}
+
+/// Like [`solve_by_expansion`], but returns [`SolverResult::Unknown`] once `interrupt` is set.
+/// The flag is checked between expansion steps, but not during SAT solving.
+#[must_use]
+pub fn solve_by_expansion_with_interrupt(qcnf: &QCNF, interrupt: &AtomicBool) -> SolverResult {
+    let mut universal_scopes = qcnf.prefix.iter().filter(|(quant, _)| *quant == QuantTy::Forall);
+    let universals: Vec<Var> =
+        universal_scopes.next().map(|(_, vars)| vars.clone()).unwrap_or_default();
+    if universal_scopes.next().is_some() {
+        error!("Universal expansion only supports a single universal scope");
+        return SolverResult::Unknown;
+    }
+    if universals.len() > MAX_UNIVERSALS {
+        warn!("Universal scope with {} variables is too large for expansion", universals.len());
+        return SolverResult::Unknown;
+    }
+    let universal_index: HashMap<Var, usize> =
+        universals.iter().enumerate().map(|(idx, &var)| (var, idx)).collect();
+    // existential variables that depend on the universals have to be copied
+    let inner: HashSet<Var> = qcnf
+        .prefix
+        .iter()
+        .skip_while(|(quant, _)| *quant == QuantTy::Exists)
+        .filter(|(quant, _)| *quant == QuantTy::Exists)
+        .flat_map(|(_, vars)| vars.iter().copied())
+        .collect();
+
+    let mut solver = LookupSolver::<Varisat>::default();
+    solver.set_var_count(qcnf.num_variables().try_into().unwrap());
+    let mut clause = Vec::new();
+    for assignment in 0_u32..(1 << universals.len()) {
+        if interrupt.load(Ordering::Relaxed) {
+            return SolverResult::Unknown;
+        }
+        debug!("expand universal assignment {assignment:#b}");
+        // fresh copies of the inner existentials
+        inner.iter().for_each(|&var| solver.forget(var));
+        for lits in &qcnf.matrix {
+            clause.clear();
+            let mut is_satisfied = false;
+            for &lit in lits {
+                if let Some(&idx) = universal_index.get(&lit.var()) {
+                    let value = assignment & (1 << idx) != 0;
+                    is_satisfied |= lit.polarity() == value;
+                } else {
+                    clause.push(solver.lookup(lit));
+                }
+            }
+            if is_satisfied {
+                continue;
+            }
+            if clause.is_empty() {
+                // clause is falsified by the universal assignment
+                return SolverResult::Unsatisfiable;
+            }
+            solver.add_clause(&clause);
+        }
+    }
+    match solver.solve() {
+        Ok(true) => SolverResult::Satisfiable,
+        Ok(false) => SolverResult::Unsatisfiable,
+        Err(err) => {
+            error!("SAT solver failed: {err}");
+            SolverResult::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::incdet::IncDet;
+    use proptest::prelude::*;
+
+    #[test]
+    fn sat() {
+        let qcnf = qcnf_formula![
+            a 1 2;
+            e 3 4;
+            // 3 <=> 1 & 2
+            1 -3; 2 -3; -1 -2 3;
+            // 4 <=> 1 | 3
+            -1 -4; -3 -4; 1 3 4;
+        ];
+        assert_eq!(solve_by_expansion(&qcnf), SolverResult::Satisfiable);
+    }
+
+    #[test]
+    fn unsat() {
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            e 3;
+            1 2 3;
+            -1 -2;
+            -3;
+        ];
+        assert_eq!(solve_by_expansion(&qcnf), SolverResult::Unsatisfiable);
+    }
+
+    #[test]
+    fn outer_existentials_are_shared() {
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            e 3;
+            1 2 -3;
+            -1 -2 3;
+            3 -2;
+            -3 2;
+        ];
+        assert_eq!(solve_by_expansion(&qcnf), SolverResult::Satisfiable);
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            1 2;
+            -1 -2;
+        ];
+        assert_eq!(solve_by_expansion(&qcnf), SolverResult::Unsatisfiable);
+    }
+
+    #[test]
+    fn multiple_universal_scopes() {
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2;
+            a 3;
+            e 4;
+            1 2 3 4;
+        ];
+        assert_eq!(solve_by_expansion(&qcnf), SolverResult::Unknown);
+    }
+
+    proptest! {
+        #[test]
+        fn agrees_with_incdet(qcnf in crate::qcnf::strategy::qcnf(1..=2, 1..5, 0..20, 1..5)) {
+            let mut solver = IncDet::from_qcnf(&qcnf);
+            assert_eq!(solve_by_expansion(&qcnf), solver.solve());
+        }
+    }
+}
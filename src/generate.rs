@@ -0,0 +1,97 @@
+//! Seeded generation of random QBF instances, e.g., for producing reproducible benchmarks.
+
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+
+use crate::{
+    literal::{Lit, Var},
+    qcnf::QCNF,
+    QuantTy,
+};
+
+/// The parameters of a randomly generated formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenParams {
+    /// The number of universally quantified variables in the outer scope.
+    pub universals: u32,
+    /// The number of existentially quantified variables in the inner scope.
+    pub existentials: u32,
+    /// The number of clauses in the matrix.
+    pub clauses: usize,
+    /// The number of literals per clause, clamped to the number of variables.
+    pub clause_width: usize,
+}
+
+impl Default for GenParams {
+    fn default() -> Self {
+        Self { universals: 10, existentials: 20, clauses: 100, clause_width: 3 }
+    }
+}
+
+/// Generates a random 2QBF $\forall x \exists y. \varphi$ with the given parameters.
+///
+/// The universal variables come first, followed by the existential variables.
+/// Every clause consists of `clause_width` distinct variables, chosen uniformly at random, with random polarity.
+/// The result depends only on `seed` and `params`.
+#[must_use]
+pub fn random_2qbf(seed: u64, params: GenParams) -> QCNF {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let num_vars = params.universals + params.existentials;
+    let vars: Vec<Var> = Var::range(num_vars.try_into().unwrap()).collect();
+    let (universals, existentials) = vars.split_at(params.universals.try_into().unwrap());
+
+    let prefix = [(QuantTy::Forall, universals), (QuantTy::Exists, existentials)]
+        .into_iter()
+        .filter(|(_, vars)| !vars.is_empty())
+        .map(|(quant, vars)| (quant, vars.to_vec()))
+        .collect();
+
+    let width = params.clause_width.min(vars.len());
+    let matrix = (0..params.clauses)
+        .map(|_| {
+            let mut clause: Vec<Lit> = index::sample(&mut rng, vars.len(), width)
+                .into_iter()
+                .map(|idx| {
+                    let [positive, negative] = vars[idx].both_literals();
+                    if rng.gen() {
+                        positive
+                    } else {
+                        negative
+                    }
+                })
+                .collect();
+            clause.sort_unstable();
+            clause
+        })
+        .collect();
+
+    QCNF { prefix, matrix, soft: Vec::new() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let params = GenParams::default();
+        assert_eq!(random_2qbf(42, params), random_2qbf(42, params));
+        assert_ne!(random_2qbf(42, params), random_2qbf(43, params));
+    }
+
+    #[test]
+    fn respects_params() {
+        let params = GenParams { universals: 3, existentials: 5, clauses: 17, clause_width: 4 };
+        let qcnf = random_2qbf(0, params);
+        assert_eq!(qcnf.prefix.len(), 2);
+        assert_eq!(qcnf.prefix[0].0, QuantTy::Forall);
+        assert_eq!(qcnf.prefix[0].1.len(), 3);
+        assert_eq!(qcnf.prefix[1].0, QuantTy::Exists);
+        assert_eq!(qcnf.prefix[1].1.len(), 5);
+        assert_eq!(qcnf.matrix.len(), 17);
+        for clause in &qcnf.matrix {
+            assert_eq!(clause.len(), 4);
+            assert!(clause.windows(2).all(|w| w[0].var() != w[1].var()));
+            assert!(clause.iter().all(|lit| lit.var().to_dimacs() <= 8));
+        }
+    }
+}
@@ -1,35 +1,48 @@
 //! Implementation of the incremental determinization algorithm.
 
 use self::{
+    activity::ClauseActivity,
     conflict::{analysis::ConflictAnalysis, check::ConflictCheck},
     graph::ImplGraph,
     propagation::{
-        assignment::{Assignment, Value},
+        assignment::Assignment,
         trail::{DecLvl, Trail},
     },
     skolem::Skolem,
-    stats::Statistics,
+    stats::{Heartbeat, Statistics},
     vsids::Vsids,
-    watch::{Watch, WatchList},
+    watch::{BinaryWatch, Watch, WatchList},
 };
 use crate::{
-    clause::alloc::{Allocator, ClauseId},
-    datastructure::{heap::VarHeap, VarVec},
+    clause::{
+        alloc::{Allocator, ClauseId},
+        is_tautology, Clause,
+    },
+    datastructure::{heap::VarHeap, LitSet, VarVec},
     incdet::graph::Impl,
-    literal::{filter_lit, filter_var, Lit, LitSlice, Var},
+    literal::{filter_var, Lit, LitSlice, Var},
     qdimacs::FromQdimacs,
-    sat::varisat::Varisat,
-    QuantTy, SolverResult,
+    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    QuantTy, SolveError, SolverResult,
 };
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     mem,
-    time::Instant,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 use tracing::{debug, error, info, trace};
 use varisat::{ExtendFormula, Solver};
 
+pub(crate) mod activity;
+mod builder;
+mod checkpoint;
 pub(crate) mod conflict;
+mod count;
+mod counterexample;
 pub(crate) mod graph;
 pub(crate) mod propagation;
 pub(crate) mod skolem;
@@ -37,12 +50,24 @@ pub(crate) mod stats;
 pub(crate) mod vsids;
 pub(crate) mod watch;
 
+pub use builder::IncDetBuilder;
+pub use conflict::check::ConflictStrategy;
+pub use counterexample::Counterexamples;
+pub use skolem::SkolemFn;
+pub use stats::{SolverProgress, WatchStats};
+
 #[cfg(test)]
 mod test;
 
-const ENABLE_CONSTANT_PROPAGATION: bool = false;
+/// The number of oversized learnt clauses after which the solve loop compacts the clause database for the first time,
+/// the threshold doubles with every compaction, see [`IncDetBuilder::max_learnt_size`].
+const LEARNT_COMPACTION_THRESHOLD: usize = 16;
+
+/// The maximal number of times the propagation priority of a variable is halved, see [`IncDet::propagation_priority`].
+const MAX_PRIORITY_PENALTY: u32 = 4;
 
 #[derive(Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct IncDet {
     vars: VarVec<VarData>,
     prefix: Vec<Scope>,
@@ -51,6 +76,11 @@ pub struct IncDet {
     skolem: Skolem,
     // queue for next propagation tests
     propagation: VarHeap<usize>,
+    /// the number of consecutive global conflict checks of a variable that found no conflict,
+    /// lowers its priority in the propagation queue, see [`IncDet::propagation_priority`]
+    futile_checks: VarVec<u32>,
+    /// the number of global conflict checks since the last decision or backtrack
+    level_global_checks: usize,
     constant_propagation: VecDeque<Lit>,
     assignment: Assignment,
     trail: Trail,
@@ -60,11 +90,80 @@ pub struct IncDet {
     conflict_check: ConflictCheck<Varisat>,
     dec_lvls: VarVec<Option<DecLvl>>,
     vsids: Vsids,
+    clause_activity: ClauseActivity,
+    /// keep clauses verbatim instead of applying universal reduction, see [`IncDet::with_universal_reduction`]
+    disable_universal_reduction: bool,
+    /// the universal literals removed from a clause by universal reduction,
+    /// `None` if they are not recorded, see [`IncDet::with_recorded_reductions`]
+    reduced_universals: Option<HashMap<ClauseId, Vec<Lit>>>,
+    /// the input line in which a clause starts, if reported by the parser, see [`IncDet::source_line`]
+    source_lines: HashMap<ClauseId, usize>,
+    /// the stored clauses by their signature, see [`IncDet::find_clause`]
+    signatures: HashMap<u64, Vec<ClauseId>>,
+    /// the stored clauses a learnt clause was derived from, see [`IncDet::unsat_core`]
+    derivations: HashMap<ClauseId, Vec<ClauseId>>,
+    /// the stored clauses that refute the formula, set if solving found it unsatisfiable
+    refutation: Vec<ClauseId>,
+    /// the stored clauses containing a variable, built on demand, see [`IncDet::clauses_containing`]
+    occurrences: Option<VarVec<Vec<ClauseId>>>,
+    /// learnt clauses with more literals are scheduled for deletion, see [`IncDetBuilder::max_learnt_size`]
+    max_learnt_size: Option<usize>,
+    /// the checks deciding whether a variable is conflicted, see [`IncDet::with_conflict_strategy`]
+    conflict_strategy: ConflictStrategy,
+    /// propagation pauses once a decision level reaches this many global conflict checks,
+    /// see [`IncDet::with_global_check_limit`]
+    global_check_limit: Option<usize>,
+    /// initialize the VSIDS activities from clause occurrences, see [`IncDet::with_vsids_seeding`]
+    seed_vsids: bool,
+    /// the order of the literals of stored clauses, see [`IncDet::with_literal_order`]
+    literal_order: LiteralOrder,
+    /// solving stops with an unknown result once the memory usage exceeds it, see [`IncDet::with_memory_limit`]
+    memory_limit: Option<usize>,
+    /// the interval of the progress lines logged during solving, see [`IncDet::with_progress_interval`]
+    progress_interval: Option<Duration>,
+    /// add learnt clauses without removing redundant literals, see [`IncDet::with_clause_minimization`]
+    disable_clause_minimization: bool,
+    /// add learnt clauses without self-subsuming resolution, see [`IncDet::with_clause_strengthening`]
+    disable_clause_strengthening: bool,
+    /// learnt clauses that exceed the size limit, they are removed by the next compaction, see [`IncDet::compact`]
+    scheduled_deletion: Vec<ClauseId>,
     /// set to true if the empty clause was added
     conflicted: bool,
+    /// cooperative cancellation, the solver stops with an unknown result once it is set
+    interrupt: Option<Arc<AtomicBool>>,
     stats: Statistics,
 }
 
+/// An opaque handle to a clause added via [`IncDet::add_input_clause`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ClauseHandle(ClauseId);
+
+/// An opaque handle to a scope added via [`IncDet::add_scope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScopeHandle(ScopeId);
+
+/// Limits on the search effort of a single call to [`IncDet::solve_with_budget`], `None` means unlimited.
+/// In contrast to a timeout, the limits are independent of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Budget {
+    /// the maximal number of conflicts
+    pub conflicts: Option<u32>,
+    /// the maximal number of decisions
+    pub decisions: Option<u32>,
+}
+
+/// The order of the literals of stored clauses, see [`IncDet::with_literal_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LiteralOrder {
+    /// the order in which the literals were given, without duplicates
+    AsGiven,
+    /// sorted by variable
+    #[default]
+    Sorted,
+    /// existential literals before universal literals, both sorted by variable
+    ExistentialFirst,
+}
+
 #[derive(Debug, Clone, Default)]
 struct VarData {
     scope: Option<ScopeId>,
@@ -83,7 +182,21 @@ struct Scope {
 #[derive(Debug, Clone)]
 pub(crate) struct Conflict {
     var: Var,
-    assignment: HashSet<Lit>,
+    assignment: LitSet,
+}
+
+impl std::fmt::Display for Conflict {
+    /// Formats the conflict as `conflict on var <var>: {<lits>}`, where the literals are sorted by variable.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "conflict on var {}: {{", self.var)?;
+        for (idx, lit) in self.assignment.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{lit}")?;
+        }
+        write!(f, "}}")
+    }
 }
 
 impl FromQdimacs for IncDet {
@@ -100,21 +213,29 @@ impl FromQdimacs for IncDet {
     }
 
     fn add_clause(&mut self, lits: &[Lit]) {
-        self._add_clause(lits);
+        self.add_input_clause(lits);
+    }
+
+    fn add_clause_at(&mut self, lits: &[Lit], line: usize) {
+        self._add_clause(lits, Some(line));
     }
 }
 
 impl IncDet {
-    #[cfg(test)]
-    fn from_qcnf(qcnf: &crate::qcnf::QCNF) -> Self {
+    pub(crate) fn from_qcnf(qcnf: &crate::qcnf::QCNF) -> Self {
         let mut solver = Self::default();
+        solver.add_qcnf(qcnf);
+        solver
+    }
+
+    /// Adds the prefix and the matrix of `qcnf`.
+    fn add_qcnf(&mut self, qcnf: &crate::qcnf::QCNF) {
         for (qty, vars) in &qcnf.prefix {
-            solver._quantify(*qty, vars);
+            self._quantify(*qty, vars);
         }
         for clause in &qcnf.matrix {
-            solver._add_clause(clause);
+            self.add_input_clause(clause);
         }
-        solver
     }
 
     fn set_var_count(&mut self, count: usize) {
@@ -127,72 +248,209 @@ impl IncDet {
         self.vsids.set_var_count(count);
         self.conflict_check.set_var_count(count);
         self.propagation.set_var_count(count);
+        self.futile_checks.set_var_count(count);
+        if let Some(occurrences) = &mut self.occurrences {
+            occurrences.set_var_count(count);
+        }
     }
 
     fn _quantify(&mut self, quant: QuantTy, vars: &[Var]) {
-        let id = match self.prefix.last_mut() {
-            Some(scope) if scope.quantifier == quant => {
-                scope.variables.extend_from_slice(vars);
-                scope.id
-            }
-            _ => {
-                let id = ScopeId(self.prefix.len());
-                let scope = Scope { id, quantifier: quant, variables: vars.into() };
-                self.prefix.push(scope);
-                id
-            }
-        };
+        if vars.is_empty() {
+            // empty scopes would only disturb the scope numbering
+            return;
+        }
+        let ScopeHandle(id) = self.add_scope(quant);
         for &var in vars {
-            if var.as_index() >= self.vars.get_var_count() {
-                self.set_var_count(var.as_index() + 1);
+            self.bind_var(id, var);
+        }
+    }
+
+    /// Binds the variables of `lits` that are not bound yet in the outermost scope, like [`QCNF::lift_from_cnf`],
+    /// the scope is added if the prefix is empty.
+    /// Returns false without binding a variable if there is a free variable and the outermost scope is universal.
+    ///
+    /// [`QCNF::lift_from_cnf`]: crate::qcnf::QCNF::lift_from_cnf
+    pub(crate) fn bind_free_variables(&mut self, lits: &[Lit]) -> bool {
+        let is_free = |solver: &Self, var: Var| {
+            solver.vars.get(var).map_or(true, |data| data.scope.is_none())
+        };
+        if !lits.iter().any(|lit| is_free(self, lit.var())) {
+            return true;
+        }
+        let id = match self.prefix.first() {
+            None => self.add_scope(QuantTy::Exists).0,
+            Some(scope) if scope.quantifier == QuantTy::Exists => scope.id,
+            Some(_) => return false,
+        };
+        for lit in lits {
+            if is_free(self, lit.var()) {
+                self.bind_var(id, lit.var());
             }
-            // let var_data = self.vars.get_or_default(var);
-            let var_data = &mut self.vars[var];
-            let other = var_data.scope.get_or_insert(id);
-            if *other != id {
+        }
+        true
+    }
+
+    /// Binds `var` in the scope `id`, a variable that is already bound in another scope is moved.
+    fn bind_var(&mut self, id: ScopeId, var: Var) {
+        if var.as_index() >= self.vars.get_var_count() {
+            self.set_var_count(var.as_index() + 1);
+        }
+        match self.vars[var].scope {
+            Some(other) if other == id => return,
+            Some(other) => {
+                if self
+                    .allocator
+                    .iter()
+                    .any(|(_, clause)| clause.lits().iter().any(|l| l.var() == var))
+                {
+                    tracing::warn!(
+                        "{} is moved to another scope after it was used in a clause, universal reduction may have been unsound",
+                        var
+                    );
+                }
                 // variable is bound twice, remove it from outer scope
                 self.prefix[other.0].variables.retain(|&other| other != var);
-                *other = id;
+            }
+            None => {}
+        }
+        self.vars[var].scope = Some(id);
+        self.prefix[id.0].variables.push(var);
+    }
+
+    /// Reorders the sorted and deduplicated literals `lits` of a clause according to the literal order,
+    /// `given` are the literals as they were added.
+    fn order_literals(&self, lits: &mut Vec<Lit>, given: &[Lit]) {
+        match self.literal_order {
+            LiteralOrder::Sorted => {}
+            LiteralOrder::AsGiven => {
+                let mut ordered = Vec::with_capacity(lits.len());
+                for &lit in given {
+                    if lits.binary_search(&lit).is_ok() && !ordered.contains(&lit) {
+                        ordered.push(lit);
+                    }
+                }
+                *lits = ordered;
+            }
+            LiteralOrder::ExistentialFirst => {
+                // the sort is stable, thus, both parts stay sorted
+                lits.sort_by_key(|lit| !self.vars[lit.var()].is_existential(&self.prefix));
+            }
+        }
+    }
+
+    /// Stores the clause in the allocator and updates the occurrence lists, recorded reductions, and source lines.
+    fn store_clause(
+        &mut self,
+        lits: &[Lit],
+        removed: Vec<Lit>,
+        source_line: Option<usize>,
+    ) -> ClauseId {
+        let clause_id = self.allocator.add(lits);
+        debug_assert!(!self.allocator[clause_id].is_tautology(), "tautologies are not stored");
+        self.signatures.entry(self.allocator[clause_id].signature()).or_default().push(clause_id);
+        if let Some(line) = source_line {
+            self.source_lines.insert(clause_id, line);
+        }
+        if let Some(reduced_universals) = &mut self.reduced_universals {
+            if !removed.is_empty() {
+                reduced_universals.insert(clause_id, removed);
             }
         }
+        if let Some(occurrences) = &mut self.occurrences {
+            lits.iter().for_each(|lit| occurrences[lit.var()].push(clause_id));
+        }
+        clause_id
+    }
+
+    /// Returns a stored clause that is equal to `lits` up to the order and duplicates of literals.
+    fn find_clause(&self, lits: &[Lit]) -> Option<ClauseId> {
+        let mut lits = Vec::from(lits);
+        lits.sort_unstable();
+        lits.dedup();
+        let clause = Clause::new(&lits);
+        self.signatures.get(&clause.signature())?.iter().copied().find(|&clause_id| {
+            let stored = &self.allocator[clause_id];
+            stored.lits().len() == lits.len() && clause.subsumes(stored)
+        })
     }
 
-    fn _add_clause(&mut self, lits: &[Lit]) {
+    /// Returns the literal implied by the learnt clause `lits` if a stored copy of it is already
+    /// an implication of this literal, i.e., adding the clause again would not change the Skolem functions.
+    ///
+    /// Learnt clauses are not reduced before the comparison, for two quantifier scopes, they are reduced
+    /// only if they are empty.
+    fn redundant_implication(&self, lits: &[Lit]) -> Option<Lit> {
+        let clause_id = self.find_clause(lits)?;
+        let mut unassigned = lits
+            .iter()
+            .filter(|lit| self.vars[lit.var()].is_existential(&self.prefix))
+            .filter(|lit| !self.assignment.is_assigned(lit.var()));
+        let &lit = unassigned.next()?;
+        if unassigned.any(|&other| other != lit) {
+            return None;
+        }
+        self.skolem[lit].implications().any(|implication| implication == clause_id).then_some(lit)
+    }
+
+    /// Adds a clause, `source_line` is the input line in which the clause starts, if known.
+    fn _add_clause(&mut self, lits: &[Lit], source_line: Option<usize>) -> Option<ClauseId> {
         debug!("Add clause: {}", LitSlice::from(lits));
         assert!(
             lits.iter().all(|&l| self.vars.get(l.var()).map_or(false, |data| data.scope.is_some())),
             "unbound variables are not supported"
         );
+        let given = lits;
         let mut lits = Vec::from(lits);
         lits.sort_unstable();
         lits.dedup();
-        if lits.iter().zip(lits.iter().skip(1)).any(|(&left, &right)| left == !right) {
+        if is_tautology(&lits) {
             // Detected tautology clause, do not add to matrix.
-            // Note: as literals are deduplicated and sorted by variable index,
-            // literals of opposing signs have to be consecutive in the clause.
-            return;
+            return None;
         }
 
-        // universal reduction
+        // universal reduction, the maximal scope is computed over existentials only,
+        // as universal literals bound after it are removed
+        let mut removed = Vec::new();
         if let Some(max_scope) = lits
             .iter()
             .filter(|lit| self.vars[lit.var()].is_existential(&self.prefix))
             .map(|lit| self.vars[lit.var()].scope())
             .max()
         {
-            // remove universal literals that are bound after every existential variable
-            lits.retain(|lit| self.vars[lit.var()].scope() <= max_scope);
+            if !self.disable_universal_reduction {
+                if self.reduced_universals.is_some() {
+                    removed
+                        .extend(lits.iter().filter(|lit| self.vars[lit.var()].scope() > max_scope));
+                }
+                // remove universal literals that are bound after every existential variable
+                lits.retain(|lit| self.vars[lit.var()].scope() <= max_scope);
+            }
         } else {
-            // no existential variables
-            tracing::warn!("empty clause was added, instance is unsatisfiable");
+            // No existential variables: every literal is universal and removed by universal reduction.
+            // As tautologies were removed before, the resulting empty clause is falsified by the universal player.
+            if let Some(line) = source_line {
+                tracing::warn!(
+                    "clause in line {line} reduces to the empty clause, instance is unsatisfiable"
+                );
+            } else {
+                tracing::warn!("clause reduces to the empty clause, instance is unsatisfiable");
+            }
             self.conflicted = true;
+            if !self.disable_universal_reduction {
+                if self.reduced_universals.is_some() {
+                    removed.append(&mut lits);
+                }
+                lits.clear();
+            }
+            // the clause is never watched, as there is no existential literal to watch
+            return Some(self.store_clause(&lits, removed, source_line));
         }
 
-        let clause_id = self.allocator.add(&lits);
+        self.order_literals(&mut lits, given);
+        let clause_id = self.store_clause(&lits, removed, source_line);
 
         // check if there is only one existential variable
         let mut singleton = None;
-        let mut no_universals = true;
         for lit in &lits {
             if self.vars[lit.var()].is_existential(&self.prefix) {
                 match singleton {
@@ -203,25 +461,10 @@ impl IncDet {
                         break;
                     }
                 }
-            } else {
-                no_universals = false;
             }
         }
         if let Some(&lit) = singleton {
-            self.skolem[lit].add_implication(clause_id, DecLvl::ROOT);
-            if ENABLE_CONSTANT_PROPAGATION && no_universals {
-                self.constant_propagation.push_back(lit);
-            } else {
-                self.propagation
-                    .add_and_set(lit.var(), self.skolem[lit].len() + self.skolem[!lit].len());
-            }
-            for univ in lits.iter().filter(|l| self.vars[l.var()].is_universal(&self.prefix)) {
-                self.graph[lit].push(Impl {
-                    lit: univ.negated(),
-                    clause: clause_id,
-                    dec_lvl: DecLvl::ROOT,
-                });
-            }
+            self.add_unique_implication(lit, clause_id, &lits);
         } else {
             // TODO: handle constant functions
             self.clauses.push(clause_id);
@@ -231,9 +474,8 @@ impl IncDet {
                     .filter(|lit| self.vars[lit.var()].is_existential(&self.prefix))
                     .filter(|l| !self.assignment.is_assigned(l.var()));
                 let watch1 = *unassigned.next().expect("there is at least one unassigned lit");
-                self.watches.add_watch(watch1, Watch { clause: clause_id });
                 if let Some(&watch2) = unassigned.next() {
-                    self.watches.add_watch(watch2, Watch { clause: clause_id });
+                    self.add_watches(clause_id, watch1, watch2);
                 } else {
                     // select an arbitrary existential literal from largest decision level
                     let max_lvl = lits
@@ -247,47 +489,741 @@ impl IncDet {
                         .iter()
                         .find(|l| self.dec_lvls[l.var()] == Some(max_lvl))
                         .expect("There is a literal with the provided decision level");
-                    self.watches.add_watch(watch2, Watch { clause: clause_id });
-                    self.skolem[watch1].add_implication(clause_id, max_lvl);
+                    self.add_watches(clause_id, watch1, watch2);
+                    self.skolem[watch1].add_implication(clause_id, lits.len(), max_lvl);
                     self.propagation.add_and_set(
                         watch1.var(),
-                        self.skolem[watch1].len() + self.skolem[!watch1].len(),
+                        Self::propagation_priority(&self.skolem, &self.futile_checks, watch1.var()),
                     );
-                    self.graph[watch1].push(Impl {
-                        lit: watch2.negated(),
-                        clause: clause_id,
-                        dec_lvl: max_lvl,
-                    });
+                    self.graph[watch1].push(Impl { clause: clause_id, dec_lvl: max_lvl });
+                }
+            }
+        }
+        Some(clause_id)
+    }
+
+    /// Watches the existential literals `watch1` and `watch2` of the clause `clause_id`.
+    /// If they are the only existential literals, the clause is watched by [`BinaryWatch`]es.
+    fn add_watches(&mut self, clause_id: ClauseId, watch1: Lit, watch2: Lit) {
+        let clause = &self.allocator[clause_id];
+        let existentials =
+            clause.iter().filter(|lit| self.vars[lit.var()].is_existential(&self.prefix)).count();
+        if existentials == 2 {
+            let len = clause.lits().len();
+            self.watches
+                .add_binary_watch(watch1, BinaryWatch { clause: clause_id, other: watch2, len });
+            self.watches
+                .add_binary_watch(watch2, BinaryWatch { clause: clause_id, other: watch1, len });
+        } else {
+            self.watches.add_watch(watch1, Watch { clause: clause_id });
+            self.watches.add_watch(watch2, Watch { clause: clause_id });
+        }
+    }
+
+    /// Returns a builder to configure the solver, see [`IncDetBuilder`].
+    #[must_use]
+    pub fn builder() -> IncDetBuilder {
+        IncDetBuilder::default()
+    }
+
+    /// Enables or disables universal reduction of added clauses (enabled by default).
+    ///
+    /// Disabling it keeps clauses verbatim, which helps to inspect and debug encodings.
+    /// This is a debugging aid only: the solver may become slower,
+    /// and the result is only guaranteed to be correct with universal reduction enabled.
+    #[must_use]
+    pub fn with_universal_reduction(mut self, enabled: bool) -> Self {
+        self.disable_universal_reduction = !enabled;
+        self
+    }
+
+    /// Selects the checks that decide whether a variable is conflicted, by default, the local check filters
+    /// the global check while it rules out enough conflicts, see [`ConflictStrategy::Both`].
+    #[must_use]
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.conflict_strategy = strategy;
+        self
+    }
+
+    /// Limits the global conflict checks of propagation to `limit` per decision level, there is no limit by default.
+    ///
+    /// Once the checks at a decision level reach the limit, propagation pauses until the next decision,
+    /// the remaining variables stay queued or are decided instead. Checks of decisions and unit clauses
+    /// are never skipped, but they count towards the limit.
+    /// The paused propagations are counted in the `deferred_propagations` statistic.
+    #[must_use]
+    pub fn with_global_check_limit(mut self, limit: usize) -> Self {
+        self.global_check_limit = Some(limit);
+        self
+    }
+
+    /// Selects the order of the literals of stored clauses, sorted by variable by default.
+    ///
+    /// The order determines which existential literals are watched, thus, it affects propagation.
+    /// With [`LiteralOrder::ExistentialFirst`], the watched literals are a prefix of the clause,
+    /// the relative order of the existential literals is the same as for [`LiteralOrder::Sorted`].
+    #[must_use]
+    pub fn with_literal_order(mut self, order: LiteralOrder) -> Self {
+        self.literal_order = order;
+        self
+    }
+
+    /// Enables or disables seeding the VSIDS activities of existential variables from their
+    /// occurrences in the clauses (disabled by default).
+    ///
+    /// Every occurrence in a clause of length `n` contributes `2^-n` to the initial activity,
+    /// such that variables occurring often in short clauses are decided first.
+    #[must_use]
+    pub fn with_vsids_seeding(mut self, enabled: bool) -> Self {
+        self.seed_vsids = enabled;
+        self
+    }
+
+    /// Limits the memory used for clauses, watches, and Skolem functions to approximately `bytes`,
+    /// there is no limit by default.
+    ///
+    /// The limit is checked after every conflict, as learnt clauses are the main source of growth.
+    /// Once it is exceeded, solving stops with [`SolverResult::Unknown`] instead of exhausting the memory,
+    /// see [`IncDet::memory_usage`] for the estimate.
+    #[must_use]
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Logs a progress line every `interval` during solving, e.g., `c 12000 conflicts, 85 conflicts/s,
+    /// 340 decisions/s, level 17`, there are no progress lines by default.
+    ///
+    /// The lines are logged at the info level, the rates refer to the time since the previous line.
+    /// In contrast to [`IncDet::solve_with_callback`], the interval is checked in every iteration
+    /// of the search, not only on conflicts.
+    #[must_use]
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = Some(interval);
+        self
+    }
+
+    /// Enables or disables the minimization of learnt clauses (enabled by default).
+    ///
+    /// Minimization removes literals implied by the other literals of the learnt clause,
+    /// which requires a recursive traversal of the implication graph on every conflict.
+    /// Disabling it skips the traversal, which helps to measure the overhead.
+    /// Strengthening is independent of this option, see [`IncDet::with_clause_strengthening`].
+    #[must_use]
+    pub fn with_clause_minimization(mut self, enabled: bool) -> Self {
+        self.disable_clause_minimization = !enabled;
+        self
+    }
+
+    /// Enables or disables the strengthening of learnt clauses (enabled by default).
+    ///
+    /// Strengthening removes a literal `l` from the learnt clause if a watched clause containing `!l`
+    /// is, apart from `!l`, a subset of the learnt clause, i.e., the resolvent subsumes the learnt clause.
+    /// The removed literals are counted in the `strengthened_literals` statistic.
+    #[must_use]
+    pub fn with_clause_strengthening(mut self, enabled: bool) -> Self {
+        self.disable_clause_strengthening = !enabled;
+        self
+    }
+
+    /// Returns the approximate number of bytes used by the stored clauses, the watches, and the Skolem functions.
+    ///
+    /// The estimate counts the literals of the clauses and the capacities of the watch lists,
+    /// the overhead of the other data structures is not included.
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        let skolem: usize =
+            self.skolem.iter().map(|(_, implications)| implications.memory_usage()).sum();
+        self.allocator.memory_usage() + self.watches.memory_usage() + skolem
+    }
+
+    /// Enables or disables recording the universal literals removed by universal reduction (disabled by default),
+    /// e.g., to emit the universal reduction steps of a proof, see [`IncDet::reduced_universals`].
+    #[must_use]
+    pub fn with_recorded_reductions(mut self, enabled: bool) -> Self {
+        self.reduced_universals = enabled.then(HashMap::new);
+        self
+    }
+
+    /// Adds a clause to the matrix and returns a handle to it.
+    /// Returns `None` if the clause is a tautology, as it is not stored.
+    ///
+    /// After solving, the solver backtracks to the root level, such that the clause is considered by
+    /// the next call to [`IncDet::solve`]. Learnt clauses and VSIDS activities are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable of the clause is not bound in the quantifier prefix,
+    /// [`crate::try_solve`] reports such inputs as [`SolveError::UnboundVariable`] instead.
+    pub fn add_input_clause(&mut self, lits: &[Lit]) -> Option<ClauseHandle> {
+        if !self.trail.decision_level().is_root() {
+            self.backtrack_to(DecLvl::ROOT);
+        }
+        self._add_clause(lits, None).map(ClauseHandle)
+    }
+
+    /// Adds multiple clauses to the matrix and returns a handle to every clause, see [`IncDet::add_input_clause`].
+    ///
+    /// Memory for all clauses is reserved upfront.
+    /// Before solving, no watches are maintained for the added clauses, as the watch list is built once
+    /// when solving starts. Afterwards, the watches are updated for every clause, like for [`IncDet::add_input_clause`].
+    pub fn add_clauses(&mut self, clauses: &[&[Lit]]) -> Vec<Option<ClauseHandle>> {
+        self.allocator.reserve(clauses.len().try_into().unwrap());
+        self.clauses.reserve(clauses.len());
+        clauses.iter().map(|clause| self.add_input_clause(clause)).collect()
+    }
+
+    /// Returns the literals of the clause referenced by `handle`.
+    /// The clause is stored after universal reduction and may differ from the added literals.
+    #[must_use]
+    pub fn clause(&self, handle: ClauseHandle) -> &[Lit] {
+        self.allocator[handle.0].lits()
+    }
+
+    /// Returns the activity score of the clause referenced by `handle`.
+    /// The score is bumped whenever the clause is resolved during conflict analysis and decays over time,
+    /// such that clauses that participated in recent conflicts have a higher score.
+    #[must_use]
+    pub fn clause_activity(&self, handle: ClauseHandle) -> f64 {
+        self.clause_activity.get(handle.0)
+    }
+
+    /// Returns the universal literals that were removed from the clause referenced by `handle` by universal reduction.
+    /// Returns `None` if reductions are not recorded, see [`IncDet::with_recorded_reductions`].
+    #[must_use]
+    pub fn reduced_universals(&self, handle: ClauseHandle) -> Option<&[Lit]> {
+        let reduced_universals = self.reduced_universals.as_ref()?;
+        Some(reduced_universals.get(&handle.0).map_or(&[], Vec::as_slice))
+    }
+
+    /// Returns the (1-based) input line in which the clause referenced by `handle` starts.
+    /// Returns `None` if the line is unknown, i.e., the clause was not parsed with
+    /// [`QdimacsParser::source_lines`](crate::qdimacs::QdimacsParser::source_lines) enabled or is learnt.
+    #[must_use]
+    pub fn source_line(&self, handle: ClauseHandle) -> Option<usize> {
+        self.source_lines.get(&handle.0).copied()
+    }
+
+    /// Returns the input clauses that participated in the refutation if the last call to [`IncDet::solve`]
+    /// found the formula unsatisfiable, and an empty vector otherwise.
+    ///
+    /// Learnt clauses are traced back to the clauses conflict analysis derived them from,
+    /// the result is sorted and contains every clause once. The core is not necessarily minimal,
+    /// as it contains every implication that defines a Skolem function the final conflict depends on.
+    /// Learnt clauses kept by [`IncDet::compact`] are reported as input clauses.
+    #[must_use]
+    pub fn unsat_core(&self) -> Vec<ClauseHandle> {
+        let mut visited = HashSet::new();
+        let mut pending = self.refutation.clone();
+        let mut core = Vec::new();
+        while let Some(clause_id) = pending.pop() {
+            if !visited.insert(clause_id) {
+                continue;
+            }
+            match self.derivations.get(&clause_id) {
+                Some(antecedents) => pending.extend(antecedents),
+                None => core.push(ClauseHandle(clause_id)),
+            }
+        }
+        core.sort_unstable();
+        core
+    }
+
+    /// Returns handles to the stored clauses containing a literal of `var`, including learnt clauses.
+    ///
+    /// The occurrence lists are built on the first call and maintained for every clause added afterwards.
+    pub fn clauses_containing(&mut self, var: Var) -> impl Iterator<Item = ClauseHandle> + '_ {
+        let occurrences = self.occurrences.get_or_insert_with(|| {
+            let mut occurrences: VarVec<Vec<ClauseId>> = VarVec::default();
+            occurrences.set_var_count(self.vars.get_var_count());
+            for (clause_id, clause) in self.allocator.iter() {
+                clause.iter().for_each(|lit| occurrences[lit.var()].push(clause_id));
+            }
+            occurrences
+        });
+        occurrences.get(var).into_iter().flatten().copied().map(ClauseHandle)
+    }
+
+    /// Returns the innermost scope if it has quantifier `quant`, otherwise, a new innermost scope is added.
+    /// Like for [`FromQdimacs::quantify`], adjacent scopes with the same quantifier are merged.
+    /// Variables are added with [`IncDet::bind`], a scope without variables is still part of the prefix.
+    ///
+    /// Handles are invalidated by [`IncDet::reset`].
+    pub fn add_scope(&mut self, quant: QuantTy) -> ScopeHandle {
+        match self.prefix.last() {
+            Some(scope) if scope.quantifier == quant => ScopeHandle(scope.id),
+            _ => {
+                let id = ScopeId(self.prefix.len());
+                self.prefix.push(Scope { id, quantifier: quant, variables: Vec::new() });
+                ScopeHandle(id)
+            }
+        }
+    }
+
+    /// Binds `var` in the scope referenced by `scope`, see [`IncDet::add_scope`].
+    /// A variable that is already bound in another scope is moved, clauses that were added before
+    /// are not updated, thus, variables should be bound before they are used in a clause.
+    pub fn bind(&mut self, scope: ScopeHandle, var: Var) {
+        assert!(scope.0 .0 < self.prefix.len(), "scope handle is invalid, the prefix was reset");
+        self.bind_var(scope.0, var);
+    }
+
+    /// Returns the quantifier prefix, starting with the outermost scope.
+    ///
+    /// The variables of a scope are listed in the order they were bound. A variable bound twice in the same
+    /// scope keeps its first position, a variable bound again in another scope is only listed there,
+    /// at the position of the last binding.
+    pub fn prefix(&self) -> impl Iterator<Item = (QuantTy, &[Var])> {
+        self.prefix.iter().map(|scope| (scope.quantifier, scope.variables.as_slice()))
+    }
+
+    /// Returns the formula the solver reasons over, i.e., the prefix and all stored clauses.
+    /// The clauses are stored after universal reduction and include the learnt clauses,
+    /// thus, they may differ from the input clauses.
+    #[must_use]
+    pub fn to_qcnf(&self) -> crate::qcnf::QCNF {
+        let prefix = self.prefix().map(|(quant, vars)| (quant, vars.to_vec())).collect();
+        let matrix = self.allocator.iter().map(|(_, clause)| clause.lits().to_vec()).collect();
+        crate::qcnf::QCNF { prefix, matrix, soft: Vec::new() }
+    }
+
+    /// Returns whether `var` is currently deterministic by propagation, i.e.,
+    /// it is assigned but its Skolem function was not fixed by a decision.
+    #[must_use]
+    pub fn is_deterministic(&self, var: Var) -> bool {
+        self.vars.get(var).is_some()
+            && self.assignment.is_assigned(var)
+            && !var.both_literals().into_iter().any(|lit| self.trail.is_decision(lit))
+    }
+
+    /// Returns the decision level at which `var` was assigned, i.e., the number of decisions before its assignment.
+    /// Returns `None` if `var` is unassigned, see [`SolverProgress::decision_level`] for the current level.
+    #[must_use]
+    pub fn assignment_level(&self, var: Var) -> Option<u32> {
+        self.dec_lvls.get(var).copied().flatten().map(u32::from)
+    }
+
+    /// Returns the decision levels from the root level upwards together with the literals assigned at them,
+    /// in the order of assignment. Except for the root level, the first literal of a level is its decision.
+    pub fn trail_levels(&self) -> impl Iterator<Item = (u32, &[Lit])> + '_ {
+        self.trail.levels().map(|(lvl, lits)| (u32::from(lvl), lits))
+    }
+
+    /// Returns the universal variables bound before the scope of `var`, i.e., the variables the Skolem function
+    /// of `var` may depend on, in the order of [`IncDet::prefix`]. Free variables have no dependencies.
+    pub fn dependencies(&self, var: Var) -> impl Iterator<Item = Var> + '_ {
+        let scope = self.vars.get(var).and_then(|data| data.scope).map_or(0, |ScopeId(idx)| idx);
+        self.prefix[..scope]
+            .iter()
+            .filter(|scope| scope.quantifier == QuantTy::Forall)
+            .flat_map(|scope| scope.variables.iter().copied())
+    }
+
+    /// Returns the quantifier and the number of variables of every scope, starting with the outermost scope.
+    #[must_use]
+    pub fn scope_sizes(&self) -> Vec<(QuantTy, usize)> {
+        self.prefix().map(|(quant, vars)| (quant, vars.len())).collect()
+    }
+
+    /// Returns the distribution of the watch-list lengths, e.g., to spot clauses that are watched by everything.
+    /// The computation is linear in the number of variables, thus, it is cheap enough for a progress callback.
+    #[must_use]
+    pub fn watch_stats(&self) -> WatchStats {
+        self.watches.stats()
+    }
+
+    /// Returns the implication clauses of the current (partial) Skolem function of `var`,
+    /// i.e., the clauses implying the positive literal and the negative literal, respectively.
+    #[must_use]
+    pub fn skolem_clauses(&self, var: Var) -> (Vec<Vec<Lit>>, Vec<Vec<Lit>>) {
+        let [positive, negative] = var.both_literals().map(|lit| {
+            self.skolem[lit]
+                .implications()
+                .map(|cid| self.allocator[cid].lits().to_vec())
+                .collect::<Vec<_>>()
+        });
+        (positive, negative)
+    }
+
+    /// Returns the current (partial) Skolem function of `var` as a list of conditional values,
+    /// see [`SkolemFn`] for the evaluation semantics.
+    /// In contrast to [`IncDet::skolem_clauses`], the conditions are already extracted from the implication clauses.
+    ///
+    /// A decided literal holds iff one of its implications applies, thus, the function of a decided variable
+    /// ends with the opposite value under the empty condition. Variables unknown to the solver have no cases.
+    #[must_use]
+    pub fn model_function(&self, var: Var) -> SkolemFn {
+        if self.vars.get(var).is_none() {
+            return SkolemFn::new(var, Vec::new());
+        }
+        let decision = self.trail.iter_decisions().find(|lit| lit.var() == var);
+        let cases = var
+            .both_literals()
+            .into_iter()
+            .flat_map(|lit| {
+                self.skolem[lit].implications().map(move |cid| {
+                    let condition = self.allocator[cid]
+                        .iter()
+                        .filter(filter_var(var))
+                        .map(|l| l.negated())
+                        .collect();
+                    (condition, lit.is_positive())
+                })
+            })
+            .chain(decision.map(|lit| (Vec::new(), lit.is_negative())))
+            .collect();
+        SkolemFn::new(var, cases)
+    }
+
+    /// Resets the solver to the state of a freshly constructed solver, such that an unrelated formula can be added.
+    ///
+    /// The prefix, input and learned clauses, trail, assignment, watches, implication graph,
+    /// Skolem functions, VSIDS scores, and statistics are cleared.
+    /// The memory of the clause allocator and the clause list is kept for reuse,
+    /// as are the interrupt flag registered by [`IncDet::set_interrupt`], the SAT trace, and the configuration.
+    pub fn reset(&mut self) {
+        let mut allocator = mem::take(&mut self.allocator);
+        allocator.clear();
+        let mut clauses = mem::take(&mut self.clauses);
+        clauses.clear();
+        let interrupt = self.interrupt.take();
+        let disable_universal_reduction = self.disable_universal_reduction;
+        let reduced_universals = self.reduced_universals.take().map(|mut reduced_universals| {
+            reduced_universals.clear();
+            reduced_universals
+        });
+        let max_learnt_size = self.max_learnt_size;
+        let conflict_strategy = self.conflict_strategy;
+        let global_check_limit = self.global_check_limit;
+        let seed_vsids = self.seed_vsids;
+        let literal_order = self.literal_order;
+        let memory_limit = self.memory_limit;
+        let progress_interval = self.progress_interval;
+        let disable_clause_minimization = self.disable_clause_minimization;
+        let disable_clause_strengthening = self.disable_clause_strengthening;
+        let vsids_decay = self.vsids.decay_factor();
+        let sat_trace = self.conflict_check.trace().cloned();
+        *self = Self {
+            clauses,
+            allocator,
+            disable_universal_reduction,
+            reduced_universals,
+            max_learnt_size,
+            conflict_strategy,
+            global_check_limit,
+            seed_vsids,
+            literal_order,
+            memory_limit,
+            progress_interval,
+            disable_clause_minimization,
+            disable_clause_strengthening,
+            interrupt,
+            ..Self::default()
+        };
+        self.vsids.set_decay(vsids_decay);
+        if let Some(trace) = sat_trace {
+            self.conflict_check = ConflictCheck::default().with_trace(trace);
+        }
+    }
+
+    /// Removes redundant clauses from the clause database and returns the number of remaining clauses.
+    ///
+    /// Removed are the learnt clauses scheduled for deletion (see [`IncDetBuilder::max_learnt_size`]),
+    /// clauses satisfied by an existential unit clause, and clauses subsumed by another clause,
+    /// of duplicate clauses, the first one is kept.
+    /// The remaining clauses are stored again, such that the watches, Skolem functions, and implication graph
+    /// refer to a dense clause database. The next call to [`IncDet::solve`] starts at the root level,
+    /// the VSIDS activities, statistics, and configuration are kept.
+    ///
+    /// Handles to clauses, e.g., returned by [`IncDet::add_input_clause`], are invalidated.
+    pub fn compact(&mut self) -> usize {
+        let deleted: HashSet<ClauseId> = self.scheduled_deletion.drain(..).collect();
+        let units: HashSet<Lit> = self
+            .allocator
+            .iter()
+            .filter_map(|(_, clause)| match clause.lits() {
+                &[lit] if self.vars[lit.var()].is_existential(&self.prefix) => Some(lit),
+                _ => None,
+            })
+            .collect();
+        let candidates: Vec<ClauseId> = self
+            .allocator
+            .iter()
+            .filter(|(clause_id, _)| !deleted.contains(clause_id))
+            .filter(|(_, clause)| {
+                clause.lits().len() <= 1 || !clause.iter().any(|lit| units.contains(lit))
+            })
+            .map(|(clause_id, _)| clause_id)
+            .collect();
+
+        // a clause is subsumed by a smaller subset, or an equal clause stored before it,
+        // the subsumed clauses contain the literal of the subset with the fewest occurrences
+        let mut occurrences: HashMap<Lit, Vec<usize>> = HashMap::new();
+        for (idx, &clause_id) in candidates.iter().enumerate() {
+            for &lit in self.allocator[clause_id].iter() {
+                occurrences.entry(lit).or_default().push(idx);
+            }
+        }
+        let mut subsumed = vec![false; candidates.len()];
+        for (idx, &clause_id) in candidates.iter().enumerate() {
+            let clause = &self.allocator[clause_id];
+            let Some(shortest) =
+                clause.iter().map(|lit| &occurrences[lit]).min_by_key(|occ| occ.len())
+            else {
+                // the empty clause subsumes every clause
+                subsumed.iter_mut().enumerate().filter(|&(other, _)| other != idx).for_each(
+                    |(_, subsumed)| *subsumed = true,
+                );
+                break;
+            };
+            for &other in shortest {
+                let other_clause = &self.allocator[candidates[other]];
+                if other != idx
+                    && (clause.lits().len() < other_clause.lits().len() || idx < other)
+                    && clause.subsumes(other_clause)
+                {
+                    subsumed[other] = true;
                 }
             }
         }
+        let remaining: Vec<_> = (0..candidates.len())
+            .filter(|&idx| !subsumed[idx])
+            .map(|idx| {
+                let clause_id = candidates[idx];
+                (
+                    self.allocator[clause_id].lits().to_vec(),
+                    self.source_lines.get(&clause_id).copied(),
+                    self.reduced_universals.as_mut().and_then(|reduced| reduced.remove(&clause_id)),
+                )
+            })
+            .collect();
+        debug!("compaction keeps {} of {} clauses", remaining.len(), self.allocator.len());
+
+        let var_count = self.vars.get_var_count();
+        let prefix = mem::take(&mut self.prefix);
+        let vsids = mem::take(&mut self.vsids);
+        let stats = mem::take(&mut self.stats);
+        let conflicted = self.conflicted;
+        self.reset();
+        self.set_var_count(var_count);
+        for scope in &prefix {
+            self._quantify(scope.quantifier, &scope.variables);
+        }
+        for (lits, source_line, removed) in remaining {
+            let clause_id = self._add_clause(&lits, source_line);
+            if let (Some(clause_id), Some(removed), Some(reduced_universals)) =
+                (clause_id, removed, &mut self.reduced_universals)
+            {
+                reduced_universals.insert(clause_id, removed);
+            }
+        }
+        self.vsids = vsids;
+        self.stats = stats;
+        self.conflicted |= conflicted;
+        self.allocator.len()
     }
 
     /// Solves the QBF using incremental determinization.
+    /// Errors are logged and reported as [`SolverResult::Unknown`], see [`IncDet::try_solve`].
     pub fn solve(&mut self) -> SolverResult {
+        self.solve_with_callback(|_| {})
+    }
+
+    /// Solves the QBF using incremental determinization.
+    /// In contrast to [`IncDet::solve`], the reasons for not solving the formula are reported as errors.
+    /// Exhausting a [`Budget`] or the memory limit is not an error, it results in [`SolverResult::Unknown`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::UnsupportedPrefix`] if the formula is not a 2QBF,
+    /// [`SolveError::SatBackend`] if the SAT solver failed,
+    /// and [`SolveError::Timeout`] if solving was interrupted (see [`IncDet::set_interrupt`]).
+    pub fn try_solve(&mut self) -> Result<SolverResult, SolveError> {
+        self.try_solve_with_callback_and_budget(&mut |_| {}, Budget::default())
+    }
+
+    /// Solves the QBF using incremental determinization.
+    /// The `callback` is invoked on every conflict with a snapshot of the solver progress.
+    pub fn solve_with_callback<F: FnMut(&SolverProgress)>(
+        &mut self,
+        mut callback: F,
+    ) -> SolverResult {
+        self.solve_with_callback_and_budget(&mut callback, Budget::default())
+    }
+
+    /// Solves the QBF using incremental determinization and returns [`SolverResult::Unknown`]
+    /// once the `budget` is exhausted.
+    /// The solver stays in a consistent state, i.e., the statistics reflect the performed search.
+    pub fn solve_with_budget(&mut self, budget: Budget) -> SolverResult {
+        self.solve_with_callback_and_budget(&mut |_| {}, budget)
+    }
+
+    fn solve_with_callback_and_budget(
+        &mut self,
+        callback: &mut dyn FnMut(&SolverProgress),
+        budget: Budget,
+    ) -> SolverResult {
+        match self.try_solve_with_callback_and_budget(callback, budget) {
+            Ok(result) => result,
+            Err(SolveError::Timeout) => {
+                info!("solving was interrupted");
+                SolverResult::Unknown
+            }
+            Err(err) => {
+                error!("{err}");
+                SolverResult::Unknown
+            }
+        }
+    }
+
+    fn try_solve_with_callback_and_budget(
+        &mut self,
+        callback: &mut dyn FnMut(&SolverProgress),
+        budget: Budget,
+    ) -> Result<SolverResult, SolveError> {
         let instant = Instant::now();
-        let result = self._solve();
+        let result = self._solve(callback, budget);
+        if matches!(result, Err(SolveError::SatBackend(_)))
+            && !self.trail.decision_level().is_root()
+        {
+            // the search cannot continue from a state in which a conflict check failed
+            self.backtrack_to(DecLvl::ROOT);
+        }
         self.stats.global.solve_time = instant.elapsed();
         info!("\n{:#?}", self.stats);
         result
     }
 
-    fn _solve(&mut self) -> SolverResult {
+    /// Registers a flag that is checked periodically during solving.
+    /// Once it is set, [`IncDet::solve`] returns [`SolverResult::Unknown`]
+    /// and [`IncDet::try_solve`] returns [`SolveError::Timeout`].
+    pub fn set_interrupt(&mut self, interrupt: Arc<AtomicBool>) {
+        self.interrupt = Some(interrupt);
+    }
+
+    /// Solves a formula without universal variables, i.e., a plain SAT problem, by the SAT backend.
+    /// The Skolem functions are not updated and the search cannot be interrupted.
+    fn solve_sat(&mut self) -> Result<SolverResult, SolveError> {
+        info!("formula has a single existential scope, solve by SAT");
+        let mut solver = LookupSolver::<Varisat>::default();
+        solver.set_var_count(self.vars.get_var_count());
+        if let Some(trace) = self.conflict_check.trace() {
+            solver.set_trace(trace, "sat");
+        }
+        // every clause is guarded by a selector, the failed selectors are the unsat core
+        let mut selectors = Vec::with_capacity(self.allocator.len());
+        for (clause_id, clause) in self.allocator.iter() {
+            let selector = solver.add_variable();
+            let mut lits: Vec<_> = clause.iter().map(|&lit| solver.lookup(lit)).collect();
+            lits.push(!selector);
+            solver.add_clause(&lits);
+            selectors.push((selector, clause_id));
+        }
+        let assumptions: Vec<_> = selectors.iter().map(|&(selector, _)| selector).collect();
+        match solver.solve_with_assumptions(&assumptions) {
+            Ok(true) => Ok(SolverResult::Satisfiable),
+            Ok(false) => {
+                let failed = solver.failed_assumptions().unwrap_or_default();
+                self.refutation = selectors
+                    .into_iter()
+                    .filter(|(selector, _)| failed.contains(selector))
+                    .map(|(_, clause_id)| clause_id)
+                    .collect();
+                Ok(SolverResult::Unsatisfiable)
+            }
+            Err(err) => Err(SolveError::SatBackend(Box::new(err))),
+        }
+    }
+
+    fn exceeds_memory_limit(&self) -> bool {
+        let Some(limit) = self.memory_limit else {
+            return false;
+        };
+        let usage = self.memory_usage();
+        if usage > limit {
+            info!("memory limit is exceeded: {usage} > {limit} bytes");
+            return true;
+        }
+        false
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.interrupt.as_ref().map_or(false, |interrupt| interrupt.load(Ordering::Relaxed))
+    }
+
+    fn progress(&self) -> SolverProgress {
+        SolverProgress {
+            decisions: self.stats.global.decisions,
+            conflicts: self.stats.global.conflicts,
+            added_clauses: self.stats.global.added_clauses,
+            decision_level: self.trail.decision_level().level(),
+            assigned: self.trail.len(),
+        }
+    }
+
+    fn _solve(
+        &mut self,
+        callback: &mut dyn FnMut(&SolverProgress),
+        budget: Budget,
+    ) -> Result<SolverResult, SolveError> {
+        self.refutation.clear();
         if self.prefix.len() > 2 {
-            error!("Only 2QBF is currently supported");
-            return SolverResult::Unknown;
+            return Err(SolveError::UnsupportedPrefix { scopes: self.prefix.len() });
         }
-        if self.conflicted {
-            return SolverResult::Unsatisfiable;
+        info!("number of clauses: {}", self.allocator.len());
+        if self.prefix.iter().all(|scope| scope.quantifier == QuantTy::Exists) {
+            if self.conflicted {
+                self.refute_by_universal_clause();
+                return Ok(SolverResult::Unsatisfiable);
+            }
+            return self.solve_sat();
         }
         self.build_watchlist();
+        if self.conflicted {
+            self.refute_by_universal_clause();
+            return Ok(SolverResult::Unsatisfiable);
+        }
         self.build_vsids_heap();
         let mut initial = Some(());
+        // the budget is relative to the effort at the start of this call
+        let start = (self.stats.global.conflicts, self.stats.global.decisions);
+        let mut heartbeat =
+            self.progress_interval.map(|interval| Heartbeat::new(interval, &self.progress()));
+        let mut compaction_threshold = LEARNT_COMPACTION_THRESHOLD;
         loop {
-            if let Some(conflict) = self.propagate() {
-                debug!("{conflict:?}");
-                if let Some(result) = self.handle_conflict(&conflict) {
-                    return result;
+            if self.is_interrupted() {
+                return Err(SolveError::Timeout);
+            }
+            if let Some(heartbeat) = heartbeat.as_mut().filter(|heartbeat| heartbeat.is_due()) {
+                info!("{}", heartbeat.report(&self.progress()));
+            }
+            let exhausted =
+                |limit: Option<u32>, used: u32| limit.map_or(false, |limit| used >= limit);
+            if exhausted(budget.conflicts, self.stats.global.conflicts - start.0)
+                || exhausted(budget.decisions, self.stats.global.decisions - start.1)
+            {
+                info!("search budget is exhausted");
+                return Ok(SolverResult::Unknown);
+            }
+            let conflict = self.propagate()?;
+            if let Some(conflict) = conflict {
+                debug!("{conflict}");
+                callback(&self.progress());
+                let result = self.handle_conflict(&conflict);
+                self.conflict_check.recycle(conflict.assignment);
+                if let Some(result) = result {
+                    return Ok(result);
+                }
+                if self.exceeds_memory_limit() {
+                    return Ok(SolverResult::Unknown);
+                }
+                if self.scheduled_deletion.len() >= compaction_threshold {
+                    // the search restarts without the oversized clauses, the growing threshold ensures termination
+                    compaction_threshold *= 2;
+                    if !self.compact_learnt_clauses() {
+                        self.refute_by_universal_clause();
+                        return Ok(SolverResult::Unsatisfiable);
+                    }
                 }
                 continue;
             }
@@ -299,8 +1235,8 @@ impl IncDet {
             };
             self.stats.global.decisions += 1;
             assert!(!self.assignment.is_assigned(var));
-            let neg_count = self.skolem[Lit::negative(var)].lit_count(&self.allocator);
-            let pos_count = self.skolem[Lit::positive(var)].lit_count(&self.allocator);
+            let neg_count = self.skolem[Lit::negative(var)].lit_count();
+            let pos_count = self.skolem[Lit::positive(var)].lit_count();
             let decision =
                 if neg_count <= pos_count { Lit::negative(var) } else { Lit::positive(var) };
             trace!(
@@ -311,59 +1247,158 @@ impl IncDet {
                 self.skolem[Lit::positive(var)].len()
             );
             // check if the decision leads to a conflict
-            if let Some(assignment) = self.is_conflicted(var, Some(decision)) {
+            let assignment = self.is_conflicted(var, Some(decision))?;
+            if let Some(assignment) = assignment {
                 trace!("{} is conflicted", var);
-                if let Some(result) = self.handle_conflict(&Conflict { var, assignment }) {
-                    return result;
+                callback(&self.progress());
+                let conflict = Conflict { var, assignment };
+                let result = self.handle_conflict(&conflict);
+                self.conflict_check.recycle(conflict.assignment);
+                if let Some(result) = result {
+                    return Ok(result);
+                }
+                if self.exceeds_memory_limit() {
+                    return Ok(SolverResult::Unknown);
                 }
                 continue;
             }
             // TODO: is_constant
             self.assign_and_propagate(decision, true, false);
         }
-        SolverResult::Satisfiable
+        Ok(SolverResult::Satisfiable)
+    }
+
+    /// Adds the implication of the only existential literal `lit` of the clause `lits`
+    /// to the Skolem function, conditioned on the negated universal literals.
+    fn add_unique_implication(&mut self, lit: Lit, clause_id: ClauseId, lits: &[Lit]) {
+        debug_assert!(lits.contains(&lit));
+        self.skolem[lit].add_implication(clause_id, lits.len(), DecLvl::ROOT);
+        let no_universals = lits.iter().all(|l| self.vars[l.var()].is_existential(&self.prefix));
+        if no_universals {
+            // unit clause, the literal is forced at the root level, see `IncDet::propagate_units`
+            self.constant_propagation.push_back(lit);
+        }
+        // the regular propagation handles units added above the root level
+        self.propagation.add_and_set(
+            lit.var(),
+            Self::propagation_priority(&self.skolem, &self.futile_checks, lit.var()),
+        );
+        // constants have no reason in the implication graph
+        if !no_universals {
+            self.graph[lit].push(Impl { clause: clause_id, dec_lvl: DecLvl::ROOT });
+        }
     }
 
+    /// Watches two existential literals of every clause.
+    /// Clauses with less than two existential literals cannot be watched,
+    /// they are turned into implications (single existential) or make the instance unsatisfiable (no existential).
     fn build_watchlist(&mut self) {
         self.watches.clear();
         self.watches.set_enabled();
-        for &cid in &self.clauses {
+        for cid in std::mem::take(&mut self.clauses) {
             let clause = &self.allocator[cid];
             let mut iter = clause
                 .lits()
                 .iter()
+                .copied()
                 .filter(|lit| self.vars[lit.var()].is_existential(&self.prefix));
-            let watch1 = *iter.next().expect("every clause has at least 2 existential variables");
-            let watch2 = *iter.next().expect("every clause has at least 2 existential variables");
-            self.watches.add_watch(watch1, Watch { clause: cid });
-            self.watches.add_watch(watch2, Watch { clause: cid });
+            match (iter.next(), iter.next()) {
+                (Some(watch1), Some(watch2)) => {
+                    self.add_watches(cid, watch1, watch2);
+                    self.clauses.push(cid);
+                }
+                (Some(lit), None) => {
+                    let lits = clause.lits().to_vec();
+                    self.add_unique_implication(lit, cid, &lits);
+                }
+                (None, _) => {
+                    tracing::warn!(
+                        "clause without existential literals, instance is unsatisfiable"
+                    );
+                    self.conflicted = true;
+                }
+            }
         }
     }
 
+    /// Adds the unassigned existential variables to the VSIDS heap.
+    ///
+    /// The activities are kept across incremental calls to [`IncDet::solve`], as they are a warm start
+    /// for the next search. Only variables that were never bumped start at the initial activity,
+    /// which is either zero or the seed (see [`IncDet::with_vsids_seeding`]).
+    /// Removes the learnt clauses that exceed the size limit during solving by [`IncDet::compact`],
+    /// the search restarts at the root level. Returns `false` if a clause without existential literals remains.
+    fn compact_learnt_clauses(&mut self) -> bool {
+        debug!("compact {} oversized learnt clauses", self.scheduled_deletion.len());
+        self.stats.global.learnt_compactions += 1;
+        self.compact();
+        self.build_watchlist();
+        if self.conflicted {
+            return false;
+        }
+        self.build_vsids_heap();
+        true
+    }
+
     fn build_vsids_heap(&mut self) {
         self.vars
             .iter()
             .filter(|(_, data)| data.is_existential(&self.prefix))
+            .filter(|(var, _)| !self.assignment.is_assigned(*var))
             .for_each(|(var, _)| self.vsids.add(var));
+        if self.seed_vsids {
+            let mut weights: VarVec<f64> = VarVec::default();
+            weights.set_var_count(self.vars.get_var_count());
+            for (_, clause) in self.allocator.iter() {
+                let weight = 0.5_f64.powi(clause.lits().len().try_into().unwrap_or(i32::MAX));
+                clause.iter().for_each(|lit| weights[lit.var()] += weight);
+            }
+            for (var, data) in self.vars.iter() {
+                // activities are positive once a variable was bumped or seeded
+                #[allow(clippy::float_cmp)]
+                let fresh = self.vsids.activity(var) == 0.0;
+                if data.is_existential(&self.prefix) && fresh {
+                    self.vsids.set_activity(var, weights[var]);
+                }
+            }
+        }
     }
 
     pub(crate) fn next_decision_variable(&self) -> Option<Var> {
         self.vsids.peek()
     }
 
-    fn propagate(&mut self) -> Option<Conflict> {
+    fn propagate(&mut self) -> Result<Option<Conflict>, SolveError> {
+        if let Some(conflict) = self.propagate_units()? {
+            return Ok(Some(conflict));
+        }
         while let Some(var) = self.propagation.pop() {
             if self.assignment.is_assigned(var) {
                 continue;
             }
-            if !self.has_unique_consequence(var) {
+            if self.global_check_limit.map_or(false, |limit| self.level_global_checks >= limit) {
+                // the queued variables are propagated after the next decision, or decided instead
+                debug!("global conflict check limit reached, pause propagation");
+                self.stats.skolem.deferred_propagations += 1;
+                self.requeue_propagation(var);
+                break;
+            }
+            let unique = self.has_unique_consequence(var).map_err(|err| {
+                self.requeue_propagation(var);
+                err
+            })?;
+            if !unique {
                 debug_assert!(!self.propagation.contained(var));
                 continue;
             }
             trace!("{} has unique consquence", var);
-            if let Some(assignment) = self.is_conflicted(var, None) {
+            let assignment = self.is_conflicted(var, None).map_err(|err| {
+                self.requeue_propagation(var);
+                err
+            })?;
+            if let Some(assignment) = assignment {
                 trace!("{} is conflicted", var);
-                return Some(Conflict { var, assignment });
+                return Ok(Some(Conflict { var, assignment }));
             }
             trace!("{} is deterministic", var);
             let lit =
@@ -374,12 +1409,53 @@ impl IncDet {
                 };
             self.assign_and_propagate(lit, false, false);
         }
-        None
+        Ok(None)
+    }
+
+    /// Returns the priority of `var` in the propagation queue, i.e., the number of its implication clauses.
+    /// The priority is halved for every consecutive global conflict check of `var` that found no conflict
+    /// (up to [`MAX_PRIORITY_PENALTY`] times), such that variables that are likely conflicted are checked first.
+    fn propagation_priority(skolem: &Skolem, futile_checks: &VarVec<u32>, var: Var) -> usize {
+        let implications = skolem[Lit::positive(var)].len() + skolem[Lit::negative(var)].len();
+        implications >> futile_checks[var].min(MAX_PRIORITY_PENALTY)
+    }
+
+    /// Schedules `var` again after a failed check, such that it is checked when solving is resumed.
+    fn requeue_propagation(&mut self, var: Var) {
+        self.propagation
+            .add_and_set(var, Self::propagation_priority(&self.skolem, &self.futile_checks, var));
+    }
+
+    /// Assigns the literals of unit clauses as constants at the root level,
+    /// they are forced without checking for a unique consequence.
+    fn propagate_units(&mut self) -> Result<Option<Conflict>, SolveError> {
+        if !self.trail.decision_level().is_root() {
+            return Ok(None);
+        }
+        while let Some(&lit) = self.constant_propagation.front() {
+            let var = lit.var();
+            if self.assignment.is_assigned(var) {
+                self.constant_propagation.pop_front();
+                continue;
+            }
+            // the unit stays queued if the check fails, such that it is checked again when solving is resumed
+            let assignment = self.is_conflicted(var, None)?;
+            self.constant_propagation.pop_front();
+            if let Some(assignment) = assignment {
+                trace!("{} is conflicted", var);
+                return Ok(Some(Conflict { var, assignment }));
+            }
+            trace!("{lit} is forced by a unit clause");
+            self.stats.skolem.constant_propagations += 1;
+            self.assign_and_propagate(lit, false, true);
+        }
+        Ok(None)
     }
 
     // update internal representation to reflect that `lit` is assigned.
     pub(crate) fn assign_and_propagate(&mut self, lit: Lit, is_decision: bool, is_constant: bool) {
         if is_decision {
+            self.level_global_checks = 0;
             self.trail.add_decision(lit);
         } else {
             self.trail.push(lit);
@@ -399,7 +1475,22 @@ impl IncDet {
         debug!("propagate function {var}");
         self.stats.skolem.function_propagations += 1;
         self.dec_lvls[var] = Some(self.trail.decision_level());
-        for lit in [Lit::positive(var), Lit::negative(var)] {
+        // the other existential literal of a binary clause is implied, unless it is assigned
+        for (_, &BinaryWatch { clause, other, len }) in self.watches.iter_containing(var) {
+            if self.assignment.is_assigned(other.var()) {
+                continue;
+            }
+            debug_assert!(self.watches.is_implied_true(other, &self.assignment));
+            trace!("New implication clause for {}: {}", other, self.allocator[clause]);
+            self.stats.skolem.binary_implications += 1;
+            self.skolem[other].add_implication(clause, len, self.trail.decision_level());
+            self.propagation.add_and_set(
+                other.var(),
+                Self::propagation_priority(&self.skolem, &self.futile_checks, other.var()),
+            );
+            self.graph[other].push(Impl { clause, dec_lvl: self.trail.decision_level() });
+        }
+        for lit in var.both_literals() {
             let mut watches = mem::take(&mut self.watches[lit]);
             watches.retain(|watch: &Watch| {
                 let clause = &self.allocator[watch.clause];
@@ -418,11 +1509,6 @@ impl IncDet {
                     trace!("New watched lit {l} in clause {}", clause);
                     return false;
                 }
-                let propagated_lit = *clause
-                    .lits()
-                    .iter()
-                    .find(|lit| lit.var() == var)
-                    .expect("this is the propagated literal");
                 // there is no other existential literal to watch for,
                 // thus, this is an implication clause for the remaining variable
                 let Some(&lit) = clause
@@ -438,23 +1524,29 @@ impl IncDet {
                 };
                 trace!("New implication clause for {}: {}", lit, clause);
 
-                self.skolem[lit].add_implication(watch.clause, self.trail.decision_level());
-                self.propagation
-                    .add_and_set(lit.var(), self.skolem[lit].len() + self.skolem[!lit].len());
+                self.skolem[lit].add_implication(
+                    watch.clause,
+                    clause.lits().len(),
+                    self.trail.decision_level(),
+                );
+                self.propagation.add_and_set(
+                    lit.var(),
+                    Self::propagation_priority(&self.skolem, &self.futile_checks, lit.var()),
+                );
                 // add the propagation reason to implication graph
-                self.graph[lit].push(Impl {
-                    lit: propagated_lit.negated(),
-                    clause: watch.clause,
-                    dec_lvl: self.trail.decision_level(),
-                });
+                self.graph[lit]
+                    .push(Impl { clause: watch.clause, dec_lvl: self.trail.decision_level() });
                 true
             });
             self.watches[lit] = watches;
         }
     }
 
-    fn has_unique_consequence(&mut self, var: Var) -> bool {
+    fn has_unique_consequence(&mut self, var: Var) -> Result<bool, SolveError> {
         self.stats.skolem.local_det_checks += 1;
+        if let Some(result) = self.trivial_unique_consequence(var) {
+            return Ok(result);
+        }
         let mut solver = Solver::new();
         for cid in self.skolem[Lit::positive(var)]
             .implications()
@@ -471,13 +1563,47 @@ impl IncDet {
                     .collect::<Vec<_>>(),
             );
         }
-        let result = solver.solve().unwrap();
-        !result
+        let result = solver.solve().map_err(|err| SolveError::SatBackend(Box::new(err)))?;
+        Ok(!result)
+    }
+
+    /// Decides [`IncDet::has_unique_consequence`] syntactically for small implication sets,
+    /// returns `None` if a SAT call is required.
+    ///
+    /// The consequence is unique iff the implication clauses without `var` are unsatisfiable.
+    fn trivial_unique_consequence(&self, var: Var) -> Option<bool> {
+        let pos = &self.skolem[Lit::positive(var)];
+        let neg = &self.skolem[Lit::negative(var)];
+        let rest = |cid: ClauseId| self.allocator[cid].iter().copied().filter(|l| l.var() != var);
+        if pos.implications().chain(neg.implications()).any(|cid| rest(cid).next().is_none()) {
+            // a unit implication yields the empty clause
+            return Some(true);
+        }
+        match (pos.len(), neg.len()) {
+            // an empty set or a single non-empty clause is satisfiable
+            (pos_len, neg_len) if pos_len + neg_len <= 1 => Some(false),
+            (1, 1) => {
+                // two non-empty clauses are unsatisfiable iff they are complementary unit clauses
+                let single = |implications: &skolem::Implications| {
+                    let mut lits = rest(implications.implications().next()?);
+                    let lit = lits.next()?;
+                    lits.next().is_none().then_some(lit)
+                };
+                Some(matches!((single(pos), single(neg)), (Some(a), Some(b)) if a == !b))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the learnt clauses that exceeded the size limit and are removed by the next compaction.
+    #[cfg(test)]
+    pub(crate) fn scheduled_for_deletion(&self) -> &[ClauseId] {
+        &self.scheduled_deletion
     }
 
     fn iter_implication_clauses(&self) -> impl Iterator<Item = ClauseId> + '_ {
         self.trail.iter().flat_map(|&lit| {
-            [lit, lit.negated()].into_iter().flat_map(|lit| self.skolem[lit].implications())
+            lit.var().both_literals().into_iter().flat_map(|lit| self.skolem[lit].implications())
         })
     }
 
@@ -490,25 +1616,93 @@ impl IncDet {
         });
         self.skolem.backtrack_to(lvl);
         self.propagation.clear();
+        self.level_global_checks = 0;
         self.graph.backtrack_to(lvl);
         self.conflict_check.backtrack_to(lvl);
     }
 
     pub(crate) fn handle_conflict(&mut self, conflict: &Conflict) -> Option<SolverResult> {
         if self.trail.decision_level().is_root() {
+            self.refute_by_conflict(conflict);
             return Some(SolverResult::Unsatisfiable);
         }
         let Ok(backtrack_to) = self.analyze(conflict) else {
-                    return Some( SolverResult::Unsatisfiable);
-                };
+            self.refute_by_conflict(conflict);
+            return Some(SolverResult::Unsatisfiable);
+        };
         debug!("conflict analysis: backtrack to {backtrack_to:?}");
         self.backtrack_to(backtrack_to);
         let clause = self.conflict_analysis.clause().to_owned();
-        self._add_clause(&clause);
-        self.stats.global.added_clauses += 1;
+        #[cfg(test)]
+        self.check_learnt_clause(&clause);
+        self.add_learnt_clause(&clause);
         assert!(!self.conflicted, "empty clause cannot be added through conflict analysis");
         None
     }
+
+    /// Records the clauses refuting the formula after a conflict that cannot be resolved by backtracking,
+    /// i.e., the implications defining the Skolem function of the conflicted variable
+    /// and, transitively, of the existential variables it depends on.
+    /// The clauses resolved by conflict analysis are among them.
+    fn refute_by_conflict(&mut self, conflict: &Conflict) {
+        let mut visited = HashSet::new();
+        let mut pending = vec![conflict.var];
+        let mut refutation = Vec::new();
+        while let Some(var) = pending.pop() {
+            if !visited.insert(var) {
+                continue;
+            }
+            for clause_id in
+                var.both_literals().into_iter().flat_map(|lit| self.skolem[lit].implications())
+            {
+                refutation.push(clause_id);
+                pending.extend(self.allocator[clause_id].iter().map(|lit| lit.var()).filter(
+                    |&other| other != var && self.vars[other].is_existential(&self.prefix),
+                ));
+            }
+        }
+        refutation.sort_unstable();
+        refutation.dedup();
+        self.refutation = refutation;
+    }
+
+    /// Records a clause without existential literals as refutation, it is falsified by the universal player.
+    fn refute_by_universal_clause(&mut self) {
+        self.refutation.extend(
+            self.allocator
+                .iter()
+                .find(|(_, clause)| {
+                    clause.iter().all(|lit| self.vars[lit.var()].is_universal(&self.prefix))
+                })
+                .map(|(clause_id, _)| clause_id),
+        );
+    }
+
+    /// Adds the clause learnt by conflict analysis after backtracking.
+    /// The clause is not added again if a stored copy is already an implication of its unassigned literal.
+    fn add_learnt_clause(&mut self, clause: &[Lit]) {
+        if let Some(lit) = self.redundant_implication(clause) {
+            // the stored copy already implies the literal, it only has to be propagated again
+            trace!("learnt clause {} is already stored", LitSlice::from(clause));
+            self.stats.global.redundant_learnt_clauses += 1;
+            self.propagation.add_and_set(
+                lit.var(),
+                Self::propagation_priority(&self.skolem, &self.futile_checks, lit.var()),
+            );
+            return;
+        }
+        let clause_id = self._add_clause(clause, None);
+        if let Some(clause_id) = clause_id {
+            self.derivations.insert(clause_id, self.conflict_analysis.antecedents().to_vec());
+        }
+        self.stats.global.added_clauses += 1;
+        if self.max_learnt_size.map_or(false, |max_size| clause.len() > max_size) {
+            // the clause is still needed to make progress, but should not be kept
+            trace!("learnt clause of size {} exceeds the limit", clause.len());
+            self.stats.global.oversized_learnt_clauses += 1;
+            self.scheduled_deletion.extend(clause_id);
+        }
+    }
 }
 
 impl From<Lit> for varisat::Lit {
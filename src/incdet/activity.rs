@@ -0,0 +1,84 @@
+//! Activity scores of clauses participating in conflict analysis
+
+use crate::clause::alloc::ClauseId;
+
+const BUMP_INITIAL: f64 = 1.0;
+const DECAY_INITIAL: f64 = 0.999;
+const RESCALE_LIMIT: f64 = f64::MAX / 16.0;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClauseActivity {
+    /// the activity values, indexed by the clause id
+    activities: Vec<f64>,
+    /// the value used for bumping activity values
+    bump: f64,
+    /// The decay factor
+    decay: f64,
+}
+
+impl Default for ClauseActivity {
+    fn default() -> Self {
+        Self { activities: Vec::new(), bump: BUMP_INITIAL, decay: DECAY_INITIAL }
+    }
+}
+
+impl ClauseActivity {
+    /// Returns the activity score of the provided clause.
+    pub(crate) fn get(&self, clause: ClauseId) -> f64 {
+        self.activities.get(clause.as_index()).copied().unwrap_or_default()
+    }
+
+    /// Increase activity score for the provided clause.
+    pub(crate) fn bump(&mut self, clause: ClauseId) {
+        let idx = clause.as_index();
+        if idx >= self.activities.len() {
+            self.activities.resize(idx + 1, 0.0);
+        }
+        self.activities[idx] += self.bump;
+        if self.activities[idx] >= RESCALE_LIMIT {
+            self.rescale();
+        }
+    }
+
+    /// Decay all clause activities.
+    pub(crate) fn decay(&mut self) {
+        self.bump /= self.decay;
+        if self.bump >= RESCALE_LIMIT {
+            self.rescale();
+        }
+    }
+
+    /// Rescale activities to prevent overflow
+    fn rescale(&mut self) {
+        let rescale_factor = RESCALE_LIMIT.recip();
+        self.activities.iter_mut().for_each(|activity| *activity *= rescale_factor);
+        self.bump *= rescale_factor;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{clause::alloc::Allocator, literal::Lit};
+
+    #[test]
+    fn bump_and_decay() {
+        let mut allocator = Allocator::default();
+        let used = allocator.add(&[Lit::from_dimacs(1), Lit::from_dimacs(2)]);
+        let unused = allocator.add(&[Lit::from_dimacs(-1), Lit::from_dimacs(2)]);
+        let recent = allocator.add(&[Lit::from_dimacs(1), Lit::from_dimacs(-2)]);
+
+        let mut activity = ClauseActivity::default();
+        for _ in 0..3 {
+            activity.bump(used);
+            activity.decay();
+        }
+        assert!(activity.get(used) > activity.get(unused));
+        assert!(activity.get(unused) <= 0.0);
+
+        // bumps after decay weigh more than earlier ones
+        activity.bump(recent);
+        activity.bump(used);
+        assert!(activity.get(used) > activity.get(recent));
+    }
+}
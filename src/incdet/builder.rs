@@ -0,0 +1,214 @@
+//! Configuration of the solver before parsing and solving.
+
+use super::{
+    conflict::check::{ConflictCheck, ConflictStrategy},
+    IncDet, LiteralOrder,
+};
+use crate::sat::SatTrace;
+use std::{
+    io::Write,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+/// Builder that configures an [`IncDet`] solver in one place.
+/// The defaults match the ones of [`IncDet::default`].
+///
+/// # Example
+/// ```
+/// use booleanium::incdet::IncDetBuilder;
+///
+/// let solver = IncDetBuilder::default().universal_reduction(false).vsids_decay(0.9).build();
+/// ```
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct IncDetBuilder {
+    universal_reduction: bool,
+    record_reductions: bool,
+    vsids_decay: Option<f64>,
+    max_learnt_size: Option<usize>,
+    conflict_strategy: ConflictStrategy,
+    global_check_limit: Option<usize>,
+    vsids_seeding: bool,
+    literal_order: LiteralOrder,
+    memory_limit: Option<usize>,
+    progress_interval: Option<Duration>,
+    clause_minimization: bool,
+    clause_strengthening: bool,
+    interrupt: Option<Arc<AtomicBool>>,
+    sat_trace: Option<SatTrace>,
+}
+
+impl Default for IncDetBuilder {
+    fn default() -> Self {
+        Self {
+            universal_reduction: true,
+            record_reductions: false,
+            vsids_decay: None,
+            max_learnt_size: None,
+            conflict_strategy: ConflictStrategy::default(),
+            global_check_limit: None,
+            vsids_seeding: false,
+            literal_order: LiteralOrder::default(),
+            memory_limit: None,
+            progress_interval: None,
+            clause_minimization: true,
+            clause_strengthening: true,
+            interrupt: None,
+            sat_trace: None,
+        }
+    }
+}
+
+impl IncDetBuilder {
+    /// Enables or disables universal reduction, see [`IncDet::with_universal_reduction`].
+    #[must_use]
+    pub fn universal_reduction(mut self, enabled: bool) -> Self {
+        self.universal_reduction = enabled;
+        self
+    }
+
+    /// Enables or disables recording the literals removed by universal reduction,
+    /// see [`IncDet::with_recorded_reductions`].
+    #[must_use]
+    pub fn record_reductions(mut self, enabled: bool) -> Self {
+        self.record_reductions = enabled;
+        self
+    }
+
+    /// Sets the factor by which VSIDS variable activities decay after every conflict.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `decay` is not in the interval `(0, 1]`.
+    #[must_use]
+    pub fn vsids_decay(mut self, decay: f64) -> Self {
+        assert!(decay > 0.0 && decay <= 1.0, "decay factor has to be in (0, 1]");
+        self.vsids_decay = Some(decay);
+        self
+    }
+
+    /// Limits the size of learnt clauses, there is no limit by default.
+    ///
+    /// Learnt clauses are still added when exceeding the limit, as they are required for progress,
+    /// but they are scheduled for deletion. Once enough clauses are scheduled, the solve loop removes them
+    /// by [`IncDet::compact`] and restarts the search at the root level, the number of scheduled clauses
+    /// that triggers the next compaction doubles every time. Thus, solving may invalidate clause handles,
+    /// and learnt clauses kept by the compaction are reported as input clauses by [`IncDet::unsat_core`].
+    /// The number of oversized clauses and compactions is reported in the statistics.
+    #[must_use]
+    pub fn max_learnt_size(mut self, max_size: usize) -> Self {
+        self.max_learnt_size = Some(max_size);
+        self
+    }
+
+    /// Selects the checks that decide whether a variable is conflicted, see [`IncDet::with_conflict_strategy`].
+    #[must_use]
+    pub fn conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.conflict_strategy = strategy;
+        self
+    }
+
+    /// Limits the global conflict checks of propagation per decision level, see [`IncDet::with_global_check_limit`].
+    #[must_use]
+    pub fn global_check_limit(mut self, limit: usize) -> Self {
+        self.global_check_limit = Some(limit);
+        self
+    }
+
+    /// Enables or disables seeding the VSIDS activities from clause occurrences,
+    /// see [`IncDet::with_vsids_seeding`].
+    #[must_use]
+    pub fn vsids_seeding(mut self, enabled: bool) -> Self {
+        self.vsids_seeding = enabled;
+        self
+    }
+
+    /// Selects the order of the literals of stored clauses, see [`IncDet::with_literal_order`].
+    #[must_use]
+    pub fn literal_order(mut self, order: LiteralOrder) -> Self {
+        self.literal_order = order;
+        self
+    }
+
+    /// Limits the approximate memory usage of the solver, see [`IncDet::with_memory_limit`].
+    #[must_use]
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Logs a progress line every `interval` during solving, see [`IncDet::with_progress_interval`].
+    #[must_use]
+    pub fn progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = Some(interval);
+        self
+    }
+
+    /// Enables or disables the minimization of learnt clauses, see [`IncDet::with_clause_minimization`].
+    #[must_use]
+    pub fn clause_minimization(mut self, enabled: bool) -> Self {
+        self.clause_minimization = enabled;
+        self
+    }
+
+    /// Enables or disables the strengthening of learnt clauses, see [`IncDet::with_clause_strengthening`].
+    #[must_use]
+    pub fn clause_strengthening(mut self, enabled: bool) -> Self {
+        self.clause_strengthening = enabled;
+        self
+    }
+
+    /// Registers an interrupt flag, see [`IncDet::set_interrupt`].
+    #[must_use]
+    pub fn interrupt(mut self, interrupt: Arc<AtomicBool>) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Writes the SAT queries of the conflict checks to `w`, which allows to replay them
+    /// independently of the solver.
+    ///
+    /// The trace is in the incremental DIMACS format over the variables of the internal SAT solvers:
+    /// clauses are terminated by `0`, every solver call is recorded as `a <assumptions> 0` followed
+    /// by a `c result` comment, and `c solver <id> <name>` comments separate the different solvers.
+    #[must_use]
+    pub fn sat_trace<W: Write + Send + 'static>(mut self, w: W) -> Self {
+        self.sat_trace = Some(SatTrace::new(w));
+        self
+    }
+
+    /// Creates the configured solver.
+    #[must_use]
+    pub fn build(self) -> IncDet {
+        let mut solver = IncDet::default()
+            .with_universal_reduction(self.universal_reduction)
+            .with_recorded_reductions(self.record_reductions)
+            .with_conflict_strategy(self.conflict_strategy)
+            .with_vsids_seeding(self.vsids_seeding)
+            .with_literal_order(self.literal_order)
+            .with_clause_minimization(self.clause_minimization)
+            .with_clause_strengthening(self.clause_strengthening);
+        solver.max_learnt_size = self.max_learnt_size;
+        solver.global_check_limit = self.global_check_limit;
+        solver.memory_limit = self.memory_limit;
+        solver.progress_interval = self.progress_interval;
+        if let Some(decay) = self.vsids_decay {
+            solver.vsids.set_decay(decay);
+        }
+        if let Some(trace) = self.sat_trace {
+            solver.conflict_check = ConflictCheck::default().with_trace(trace);
+        }
+        if let Some(interrupt) = self.interrupt {
+            solver.set_interrupt(interrupt);
+        }
+        solver
+    }
+
+    /// Creates the configured solver for the prefix and matrix of `qcnf`, see [`IncDet::from_qcnf`].
+    pub(crate) fn build_from_qcnf(self, qcnf: &crate::qcnf::QCNF) -> IncDet {
+        let mut solver = self.build();
+        solver.add_qcnf(qcnf);
+        solver
+    }
+}
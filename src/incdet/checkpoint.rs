@@ -0,0 +1,309 @@
+//! Saving and restoring the solver state, e.g., to resume a preempted solving process.
+//!
+//! The binary format starts with the magic bytes `BLMS` and a format version,
+//! followed by the number of variables, the quantifier prefix, all stored clauses
+//! (including learnt clauses) with their source lines and recorded reductions, the configuration,
+//! the VSIDS scores, and the statistics.
+//! All integers are stored in little-endian byte order.
+
+use super::{ConflictStrategy, IncDet, LiteralOrder, QuantTy};
+use crate::literal::{Lit, Var};
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+const MAGIC: &[u8; 4] = b"BLMS";
+/// The format version, it is incremented whenever the stored fields change.
+const VERSION: u32 = 4;
+
+impl IncDet {
+    /// Writes a checkpoint of the solver to `w`, which can be restored with [`IncDet::load_state`].
+    ///
+    /// The checkpoint contains the prefix, the input and learnt clauses, the configuration, the VSIDS scores,
+    /// and the statistics. The interrupt flag and the SAT trace are not part of the checkpoint.
+    /// The partial Skolem functions, trail, and assignment are derived from the clauses when loading,
+    /// i.e., a restored solver resumes from the root decision level like after a restart.
+    ///
+    /// # Errors
+    ///
+    /// Propagates failures of the underlying writer.
+    pub fn save_state<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        write_u32(w, VERSION)?;
+
+        let num_vars = self.vars.get_var_count();
+        write_len(w, num_vars)?;
+        write_len(w, self.prefix.len())?;
+        for scope in &self.prefix {
+            w.write_all(&[u8::from(scope.quantifier == QuantTy::Forall)])?;
+            write_len(w, scope.variables.len())?;
+            for var in &scope.variables {
+                write_u32(w, var.debug_index())?;
+            }
+        }
+        write_len(w, self.allocator.len())?;
+        for (clause_id, clause) in self.allocator.iter() {
+            write_lits(w, clause.lits())?;
+            let line = self.source_lines.get(&clause_id);
+            write_u64(w, line.map_or(u64::MAX, |&line| line.try_into().unwrap()))?;
+            let removed =
+                self.reduced_universals.as_ref().and_then(|reduced| reduced.get(&clause_id));
+            write_lits(w, removed.map_or(&[], Vec::as_slice))?;
+        }
+
+        self.write_configuration(w)?;
+
+        write_f64(w, self.vsids.bump_value())?;
+        write_f64(w, self.vsids.decay_factor())?;
+        for var in Var::range(num_vars) {
+            write_f64(w, self.vsids.activity(var))?;
+        }
+
+        let stats = &self.stats.global;
+        for value in [stats.decisions, stats.conflicts, stats.added_clauses] {
+            write_u32(w, value)?;
+        }
+        Ok(())
+    }
+
+    /// Restores a solver from a checkpoint written by [`IncDet::save_state`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the checkpoint is malformed
+    /// or was written in an unsupported version, and propagates failures of the underlying reader.
+    pub fn load_state<R: Read>(mut r: R) -> io::Result<IncDet> {
+        let r = &mut r;
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a solver checkpoint"));
+        }
+        if read_u32(r)? != VERSION {
+            return Err(invalid_data("unsupported checkpoint version"));
+        }
+
+        let mut solver = IncDet::default();
+        let num_vars = read_len(r)?;
+        if num_vars > Var::MAX_VAR.as_index() {
+            return Err(invalid_data("variable count out of bound"));
+        }
+        solver.set_var_count(num_vars);
+        let read_var = |r: &mut R| {
+            let index = read_u32(r)?;
+            if usize::try_from(index).unwrap() >= num_vars {
+                return Err(invalid_data("variable out of bound"));
+            }
+            Ok(Var::from_index(index))
+        };
+
+        let num_scopes = read_len(r)?;
+        let mut vars = Vec::new();
+        for _ in 0..num_scopes {
+            let quant = match read_u8(r)? {
+                0 => QuantTy::Exists,
+                1 => QuantTy::Forall,
+                _ => return Err(invalid_data("invalid quantifier")),
+            };
+            vars.clear();
+            for _ in 0..read_len(r)? {
+                vars.push(read_var(r)?);
+            }
+            solver._quantify(quant, &vars);
+        }
+
+        let read_lits = |r: &mut R, solver: &IncDet| {
+            let mut lits = Vec::new();
+            for _ in 0..read_len(r)? {
+                let lit = Lit::from_index(read_len(r)?);
+                if lit.var().as_index() >= num_vars || solver.vars[lit.var()].scope.is_none() {
+                    return Err(invalid_data("literal of unbound variable"));
+                }
+                lits.push(lit);
+            }
+            Ok(lits)
+        };
+        let mut clauses = Vec::new();
+        let num_clauses = read_len(r)?;
+        for _ in 0..num_clauses {
+            let clause = read_lits(r, &solver)?;
+            let line = match read_u64(r)? {
+                u64::MAX => None,
+                line => Some(line.try_into().map_err(|_| invalid_data("invalid source line"))?),
+            };
+            let removed = read_lits(r, &solver)?;
+            clauses.push((clause, line, removed));
+        }
+
+        solver.read_configuration(r)?;
+        // the configuration is needed before the clauses are added
+        for (clause, line, removed) in clauses {
+            let clause_id = solver._add_clause(&clause, line);
+            if let (Some(clause_id), Some(reduced_universals)) =
+                (clause_id, &mut solver.reduced_universals)
+            {
+                if !removed.is_empty() {
+                    reduced_universals.insert(clause_id, removed);
+                }
+            }
+        }
+
+        let bump = read_f64(r)?;
+        let decay = read_f64(r)?;
+        if bump.is_nan() || !(decay > 0.0 && decay <= 1.0) {
+            return Err(invalid_data("invalid VSIDS parameters"));
+        }
+        solver.vsids.set_bump_value(bump);
+        solver.vsids.set_decay(decay);
+        for var in Var::range(num_vars) {
+            let activity = read_f64(r)?;
+            if activity.is_nan() {
+                return Err(invalid_data("invalid VSIDS activity"));
+            }
+            solver.vsids.set_activity(var, activity);
+        }
+
+        let stats = &mut solver.stats.global;
+        stats.decisions = read_u32(r)?;
+        stats.conflicts = read_u32(r)?;
+        stats.added_clauses = read_u32(r)?;
+        Ok(solver)
+    }
+
+    /// Writes the configuration of the solver, every option set by the [`super::IncDetBuilder`] except
+    /// the VSIDS decay, which is stored with the VSIDS scores, the interrupt flag, and the SAT trace.
+    fn write_configuration<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&[u8::from(self.disable_universal_reduction)])?;
+        w.write_all(&[u8::from(self.reduced_universals.is_some())])?;
+        write_u64(w, self.max_learnt_size.map_or(u64::MAX, |size| size.try_into().unwrap()))?;
+        let conflict_strategy = match self.conflict_strategy {
+            ConflictStrategy::AlwaysLocal => 0,
+            ConflictStrategy::Global => 1,
+            ConflictStrategy::Both => 2,
+        };
+        w.write_all(&[conflict_strategy])?;
+        write_u64(w, self.global_check_limit.map_or(u64::MAX, |limit| limit.try_into().unwrap()))?;
+        w.write_all(&[u8::from(self.seed_vsids)])?;
+        let literal_order = match self.literal_order {
+            LiteralOrder::AsGiven => 0,
+            LiteralOrder::Sorted => 1,
+            LiteralOrder::ExistentialFirst => 2,
+        };
+        w.write_all(&[literal_order])?;
+        write_u64(w, self.memory_limit.map_or(u64::MAX, |limit| limit.try_into().unwrap()))?;
+        w.write_all(&[u8::from(self.progress_interval.is_some())])?;
+        let interval = self.progress_interval.unwrap_or_default();
+        write_u64(w, interval.as_secs())?;
+        write_u32(w, interval.subsec_nanos())?;
+        w.write_all(&[u8::from(self.disable_clause_minimization)])?;
+        w.write_all(&[u8::from(self.disable_clause_strengthening)])?;
+        Ok(())
+    }
+
+    /// Reads the configuration written by [`IncDet::write_configuration`].
+    fn read_configuration<R: Read>(&mut self, r: &mut R) -> io::Result<()> {
+        self.disable_universal_reduction = read_bool(r)?;
+        self.reduced_universals = read_bool(r)?.then(HashMap::new);
+        self.max_learnt_size = match read_u64(r)? {
+            u64::MAX => None,
+            size => Some(size.try_into().map_err(|_| invalid_data("invalid size limit"))?),
+        };
+        self.conflict_strategy = match read_u8(r)? {
+            0 => ConflictStrategy::AlwaysLocal,
+            1 => ConflictStrategy::Global,
+            2 => ConflictStrategy::Both,
+            _ => return Err(invalid_data("invalid conflict strategy")),
+        };
+        self.global_check_limit = match read_u64(r)? {
+            u64::MAX => None,
+            limit => Some(limit.try_into().map_err(|_| invalid_data("invalid check limit"))?),
+        };
+        self.seed_vsids = read_bool(r)?;
+        self.literal_order = match read_u8(r)? {
+            0 => LiteralOrder::AsGiven,
+            1 => LiteralOrder::Sorted,
+            2 => LiteralOrder::ExistentialFirst,
+            _ => return Err(invalid_data("invalid literal order")),
+        };
+        self.memory_limit = match read_u64(r)? {
+            u64::MAX => None,
+            limit => Some(limit.try_into().map_err(|_| invalid_data("invalid memory limit"))?),
+        };
+        let has_progress_interval = read_bool(r)?;
+        let secs = read_u64(r)?;
+        let nanos = read_u32(r)?;
+        if nanos >= 1_000_000_000 {
+            return Err(invalid_data("invalid progress interval"));
+        }
+        self.progress_interval = has_progress_interval.then(|| Duration::new(secs, nanos));
+        self.disable_clause_minimization = read_bool(r)?;
+        self.disable_clause_strengthening = read_bool(r)?;
+        Ok(())
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_u32<W: Write>(w: &mut W, value: u32) -> io::Result<()> {
+    w.write_all(&value.to_le_bytes())
+}
+
+fn write_u64<W: Write>(w: &mut W, value: u64) -> io::Result<()> {
+    w.write_all(&value.to_le_bytes())
+}
+
+fn write_f64<W: Write>(w: &mut W, value: f64) -> io::Result<()> {
+    w.write_all(&value.to_le_bytes())
+}
+
+fn write_len<W: Write>(w: &mut W, len: usize) -> io::Result<()> {
+    write_u32(w, len.try_into().map_err(|_| invalid_data("length exceeds u32"))?)
+}
+
+fn write_lits<W: Write>(w: &mut W, lits: &[Lit]) -> io::Result<()> {
+    write_len(w, lits.len())?;
+    for lit in lits {
+        write_len(w, lit.debug_index())?;
+    }
+    Ok(())
+}
+
+fn read_u8<R: Read>(r: &mut R) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_bool<R: Read>(r: &mut R) -> io::Result<bool> {
+    match read_u8(r)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(invalid_data("invalid flag")),
+    }
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f64<R: Read>(r: &mut R) -> io::Result<f64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(f64::from_le_bytes(buf))
+}
+
+fn read_len<R: Read>(r: &mut R) -> io::Result<usize> {
+    Ok(read_u32(r)?.try_into().unwrap())
+}
@@ -1,4 +1,7 @@
+#[cfg(test)]
+use crate::sat::{varisat::Varisat, LookupSolver, SatSolver};
 use crate::{
+    clause::alloc::ClauseId,
     datastructure::VarVec,
     incdet::propagation::trail::{DecLvl, Trail},
     incdet::{vsids::Vsids, Conflict, IncDet, Scope, VarData},
@@ -10,6 +13,8 @@ use tracing::{debug, trace};
 pub(crate) struct ConflictAnalysis {
     clause: Vec<Lit>,
     current_level_count: usize,
+    /// the stored clauses resolved to derive the learnt clause
+    antecedents: Vec<ClauseId>,
 }
 
 impl ConflictAnalysis {
@@ -17,9 +22,15 @@ impl ConflictAnalysis {
         &self.clause
     }
 
+    /// Returns the stored clauses the learnt clause was derived from, see [`IncDet::unsat_core`].
+    pub(crate) fn antecedents(&self) -> &[ClauseId] {
+        &self.antecedents
+    }
+
     fn reset(&mut self) {
         self.clause.clear();
         self.current_level_count = 0;
+        self.antecedents.clear();
     }
 
     fn add_literal(
@@ -75,43 +86,7 @@ impl IncDet {
         self.conflict_analysis.reset();
         self.vsids.bump(conflict.var);
 
-        // start with the nucleus (-l, l)
-        for implication in &self.graph[conflict.var.negative()] {
-            let other = &self.allocator[implication.clause];
-            if other.iter().any(|l| conflict.assignment.contains(l)) {
-                continue;
-            }
-            // dbg!(implication);
-            for &lit in other.iter().filter(filter_lit(conflict.var.negative())) {
-                self.conflict_analysis.add_literal(
-                    &self.vars,
-                    &self.prefix,
-                    &self.dec_lvls,
-                    &self.trail,
-                    &mut self.vsids,
-                    lit,
-                );
-            }
-            break;
-        }
-        for implication in &self.graph[conflict.var.positive()] {
-            let other = &self.allocator[implication.clause];
-            if other.iter().any(|l| conflict.assignment.contains(l)) {
-                continue;
-            }
-            // dbg!(implication);
-            for &lit in other.iter().filter(filter_lit(conflict.var.positive())) {
-                self.conflict_analysis.add_literal(
-                    &self.vars,
-                    &self.prefix,
-                    &self.dec_lvls,
-                    &self.trail,
-                    &mut self.vsids,
-                    lit,
-                );
-            }
-            break;
-        }
+        self.analyze_nucleus(conflict);
         tracing::debug!(
             "conflict clause before analysis: {}",
             LitSlice::from(self.conflict_analysis.clause.as_slice())
@@ -124,6 +99,7 @@ impl IncDet {
             }
             let backtrack_to = self.conflict_analysis.get_backtrack_level(&self.dec_lvls, max_lvl);
             self.vsids.decay();
+            self.clause_activity.decay();
 
             tracing::debug!("Backtrack to level {backtrack_to}");
             return Ok(backtrack_to);
@@ -133,6 +109,7 @@ impl IncDet {
                 .conflict_analysis
                 .get_backtrack_level(&self.dec_lvls, self.trail.decision_level());
             self.vsids.decay();
+            self.clause_activity.decay();
             tracing::debug!("Backtrack to level {backtrack_to}");
             return Ok(backtrack_to);
         }
@@ -151,6 +128,9 @@ impl IncDet {
                     continue;
                 }
                 trace!("{lit} reason {reason}");
+                self.conflict_analysis.antecedents.push(implication.clause);
+                self.clause_activity.bump(implication.clause);
+                self.stats.global.clause_bumps += 1;
                 // dbg!(implication);
                 self.conflict_analysis.current_level_count -= 1;
                 self.conflict_analysis.clause.retain(|l| l.var() != lit.var());
@@ -179,17 +159,81 @@ impl IncDet {
             self.conflict_analysis.get_backtrack_level(&self.dec_lvls, self.trail.decision_level());
 
         self.vsids.decay();
+        self.clause_activity.decay();
 
         debug!("Backtrack to level {backtrack_to}");
         Ok(backtrack_to)
     }
 
+    /// Starts the conflict clause with the resolvent of the nucleus (-l, l) of the conflicted variable.
+    fn analyze_nucleus(&mut self, conflict: &Conflict) {
+        for implication in &self.graph[conflict.var.negative()] {
+            let other = &self.allocator[implication.clause];
+            if other.iter().any(|l| conflict.assignment.contains(*l)) {
+                continue;
+            }
+            // dbg!(implication);
+            self.conflict_analysis.antecedents.push(implication.clause);
+            self.clause_activity.bump(implication.clause);
+            self.stats.global.clause_bumps += 1;
+            for &lit in other.iter().filter(filter_lit(conflict.var.negative())) {
+                self.conflict_analysis.add_literal(
+                    &self.vars,
+                    &self.prefix,
+                    &self.dec_lvls,
+                    &self.trail,
+                    &mut self.vsids,
+                    lit,
+                );
+            }
+            break;
+        }
+        for implication in &self.graph[conflict.var.positive()] {
+            let other = &self.allocator[implication.clause];
+            if other.iter().any(|l| conflict.assignment.contains(*l)) {
+                continue;
+            }
+            // dbg!(implication);
+            self.conflict_analysis.antecedents.push(implication.clause);
+            self.clause_activity.bump(implication.clause);
+            self.stats.global.clause_bumps += 1;
+            for &lit in other.iter().filter(filter_lit(conflict.var.positive())) {
+                self.conflict_analysis.add_literal(
+                    &self.vars,
+                    &self.prefix,
+                    &self.dec_lvls,
+                    &self.trail,
+                    &mut self.vsids,
+                    lit,
+                );
+            }
+            break;
+        }
+    }
+
+    /// Removes redundant literals (unless disabled by [`IncDet::with_clause_minimization`]) and strengthens
+    /// the learnt clause (unless disabled by [`IncDet::with_clause_strengthening`]), both options are independent.
     fn minimize_learnt_clause(&mut self, conflict: &Conflict) {
+        if !self.disable_clause_minimization {
+            self.remove_redundant_literals(conflict);
+        }
+        if !self.disable_clause_strengthening {
+            self.strengthen_learnt_clause();
+        }
+        debug!(
+            "learnt clause after minimization: {}",
+            LitSlice::from(self.conflict_analysis.clause.as_slice())
+        );
+    }
+
+    /// Removes the literals that are implied by the other literals of the learnt clause.
+    fn remove_redundant_literals(&mut self, conflict: &Conflict) {
         trace!(
             "clause minimization for clause {}",
             LitSlice::from(self.conflict_analysis.clause.as_slice())
         );
         let mut redundant = Vec::new();
+        let mut antecedents = Vec::new();
         for &lit in &self.conflict_analysis.clause {
             trace!("{lit}");
             let dec_lvl = self.dec_lvls[lit.var()].unwrap_or(DecLvl::ROOT);
@@ -197,21 +241,63 @@ impl IncDet {
                 // We keep the single literal at the current decision level
                 continue;
             }
-            if self.is_literal_redundant(lit, conflict) {
+            let mut reasons = Vec::new();
+            if self.is_literal_redundant(lit, conflict, &mut reasons) {
                 redundant.push(lit);
+                antecedents.append(&mut reasons);
             }
         }
         trace!("Redundant literals: {}", LitSlice::from(redundant.as_slice()));
+        self.conflict_analysis.antecedents.append(&mut antecedents);
 
         self.conflict_analysis.clause.retain(|l| !redundant.contains(l));
+        self.stats.global.minimized_literals += u32::try_from(redundant.len()).unwrap();
+    }
 
-        debug!(
-            "learnt clause after minimization: {}",
-            LitSlice::from(self.conflict_analysis.clause.as_slice())
-        );
+    /// Removes literals `lit` from the learnt clause `C` if there is a watched clause `D`
+    /// containing `!lit` such that `D \ {!lit}` is a subset of `C \ {lit}`.
+    /// In this case, the resolvent of `C` and `D` is `C \ {lit}`, i.e., it subsumes `C`.
+    fn strengthen_learnt_clause(&mut self) {
+        let mut strengthened = Vec::new();
+        let mut resolved = Vec::new();
+        for &lit in &self.conflict_analysis.clause {
+            if self.vars[lit.var()].is_universal(&self.prefix) {
+                // Q-resolution requires an existential pivot
+                continue;
+            }
+            let dec_lvl = self.dec_lvls[lit.var()].unwrap_or(DecLvl::ROOT);
+            if dec_lvl == self.trail.decision_level() {
+                // We keep the single literal at the current decision level
+                continue;
+            }
+            let watched = self.watches[!lit].iter().map(|watch| watch.clause);
+            let binary = self.watches.binary(!lit).iter().map(|watch| watch.clause);
+            let subsuming = binary.chain(watched).find(|&clause| {
+                self.allocator[clause]
+                    .iter()
+                    .filter(filter_lit(!lit))
+                    .all(|l| self.conflict_analysis.clause.contains(l) && !strengthened.contains(l))
+            });
+            if let Some(clause) = subsuming {
+                trace!("strengthen learnt clause by removing {lit}");
+                strengthened.push(lit);
+                resolved.push(clause);
+            }
+        }
+
+        self.conflict_analysis.clause.retain(|l| !strengthened.contains(l));
+        self.conflict_analysis.antecedents.append(&mut resolved);
+        self.stats.global.strengthened_literals += u32::try_from(strengthened.len()).unwrap();
     }
 
-    fn is_literal_redundant(&self, lit: Lit, conflict: &Conflict) -> bool {
+    /// Returns true if `lit` is implied by the other literals of the learnt clause,
+    /// the reason clauses of the implication are added to `reasons`.
+    fn is_literal_redundant(
+        &self,
+        lit: Lit,
+        conflict: &Conflict,
+        reasons: &mut Vec<ClauseId>,
+    ) -> bool {
         trace!("check if {lit} is redundant");
 
         if self.vars[lit.var()].is_universal(&self.prefix) {
@@ -221,6 +307,12 @@ impl IncDet {
             return false;
         }
         // assert!(!self.graph[!lit].is_empty()); // doesn't hold if variable is in singleton clause
+        // literals of unit clauses are constants, they have no reasons in the implication graph
+        reasons.extend(
+            self.skolem[!lit]
+                .implications()
+                .filter(|&clause_id| self.allocator[clause_id].lits().len() == 1),
+        );
         for implication in &self.graph[!lit] {
             let reason = implication.reason(&self.allocator);
             trace!("{reason}");
@@ -228,13 +320,41 @@ impl IncDet {
             if !reason.is_implied(!lit, &conflict.assignment) {
                 continue;
             }
+            reasons.push(implication.clause);
 
             for &premise in reason.iter().filter(filter_lit(!lit)) {
-                if !self.is_literal_redundant(premise, conflict) {
+                if !self.is_literal_redundant(premise, conflict, reasons) {
                     return false;
                 }
             }
         }
         true
     }
+
+    /// Checks that the learnt `clause` is entailed by the stored clauses, i.e., that the
+    /// stored clauses together with the negation of `clause` are unsatisfiable.
+    ///
+    /// Conflict analysis only resolves stored clauses, universal reduction is applied
+    /// afterwards when the clause is stored. The check solves a SAT problem over all stored
+    /// clauses for every learnt clause, thus, it is only enabled in the unit tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the learnt clause is not entailed.
+    #[cfg(test)]
+    pub(crate) fn check_learnt_clause(&self, clause: &[Lit]) {
+        let mut solver = LookupSolver::<Varisat>::default();
+        solver.set_var_count(self.vars.get_var_count());
+        for (_, stored) in self.allocator.iter() {
+            let lits: Vec<_> = stored.iter().map(|&lit| solver.lookup(lit)).collect();
+            solver.add_clause(&lits);
+        }
+        let assumptions: Vec<_> = clause.iter().map(|&lit| solver.lookup(!lit)).collect();
+        let result = solver.solve_with_assumptions(&assumptions);
+        assert!(
+            matches!(result, Ok(false)),
+            "learnt clause {} is not entailed by the stored clauses",
+            LitSlice::from(clause)
+        );
+    }
 }
@@ -1,16 +1,42 @@
 //! (Incremental) conflict checking
 
 use crate::{
+    datastructure::LitSet,
     incdet::propagation::trail::DecLvl,
     incdet::IncDet,
     literal::{filter_lit, Lit, Var},
-    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    sat::{varisat::Varisat, LookupSolver, SatSolver, SatTrace},
+    SolveError,
 };
 use derivative::Derivative;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::BTreeMap;
 use tracing::{debug, trace};
 
 const INCREMENTAL_CONFLICT_CHECK: bool = false;
+/// Reuse a persistent SAT solver for the local conflict check, see [`IncDet::is_locally_conflicted`].
+const INCREMENTAL_LOCAL_CONFLICT_CHECK: bool = true;
+/// The number of retired local checks after which the persistent local solver is rebuilt.
+const LOCAL_SOLVER_RESET: u32 = 1024;
+/// The number of local checks after which their benefit is evaluated, see [`ConflictCheck::record_local_check`].
+const LOCAL_CHECK_WINDOW: u32 = 64;
+/// The local check is paused if it ruled out fewer conflicts in a window.
+const MIN_LOCAL_RULE_OUTS: u32 = 4;
+/// The number of conflict checks that skip the local check once it is paused.
+const LOCAL_CHECK_PAUSE: u32 = 256;
+
+/// Selects the checks that decide whether a variable is conflicted, see [`IncDet::with_conflict_strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictStrategy {
+    /// The fast local check that considers the implication clauses of the variable itself before every global check,
+    /// it is never paused. The local check over-approximates conflicts, thus, it only rules out conflicts,
+    /// the conflicts it reports are confirmed by the global check.
+    AlwaysLocal,
+    /// Only the complete global check that considers all Skolem functions.
+    Global,
+    /// The local check as a filter before the global check, it is paused while it rarely rules out conflicts.
+    #[default]
+    Both,
+}
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -19,17 +45,133 @@ pub(crate) struct ConflictCheck<S: SatSolver> {
     sat_solver: LookupSolver<S>,
     #[derivative(Debug = "ignore")]
     assumptions: BTreeMap<DecLvl, S::Lit>,
+    #[derivative(Debug = "ignore")]
+    local_solver: LookupSolver<S>,
+    /// the selector of every premise encoded in the local solver, see [`ConflictCheck::local_premise`]
+    #[derivative(Debug = "ignore")]
+    premises: BTreeMap<Vec<Lit>, S::Lit>,
+    /// the number of local checks whose constraints are disabled in the local solver
+    retired_local_checks: u32,
+    /// records the SAT queries of the conflict checks, see [`ConflictCheck::with_trace`]
+    trace: Option<SatTrace>,
+    /// the number of local checks in the current window
+    local_checks: u32,
+    /// the number of local checks in the current window that ruled out a conflict
+    local_rule_outs: u32,
+    /// the number of remaining conflict checks that skip the local check
+    local_pause: u32,
+    /// the memory of the last conflicting assignment, see [`ConflictCheck::conflicting_assignment`]
+    assignment: LitSet,
 }
 
 impl<S: SatSolver> Default for ConflictCheck<S> {
     fn default() -> Self {
-        Self { sat_solver: LookupSolver::default(), assumptions: BTreeMap::default() }
+        Self {
+            sat_solver: LookupSolver::default(),
+            assumptions: BTreeMap::default(),
+            local_solver: LookupSolver::default(),
+            premises: BTreeMap::default(),
+            retired_local_checks: 0,
+            trace: None,
+            local_checks: 0,
+            local_rule_outs: 0,
+            local_pause: 0,
+            assignment: LitSet::default(),
+        }
     }
 }
 
 impl<S: SatSolver> ConflictCheck<S> {
+    /// Records every clause and solver call of the SAT queries in `trace`.
+    /// The literals are the ones of the SAT solvers, i.e., after mapping the variables of the formula.
+    pub(crate) fn with_trace(mut self, trace: SatTrace) -> Self {
+        self.sat_solver.set_trace(&trace, "incremental");
+        self.local_solver.set_trace(&trace, "local");
+        self.trace = Some(trace);
+        self
+    }
+
+    pub(crate) fn trace(&self) -> Option<&SatTrace> {
+        self.trace.as_ref()
+    }
+
+    /// Returns whether the local check should run before the global check, i.e., it is not paused.
+    fn run_local_check(&mut self) -> bool {
+        if self.local_pause == 0 {
+            return true;
+        }
+        self.local_pause -= 1;
+        false
+    }
+
+    /// Records whether a local check ruled out a conflict. At the end of every window of [`LOCAL_CHECK_WINDOW`]
+    /// local checks, the local check is paused if it ruled out fewer than [`MIN_LOCAL_RULE_OUTS`] conflicts,
+    /// as the global check had to run anyway. Returns whether the local check was paused.
+    fn record_local_check(&mut self, ruled_out: bool) -> bool {
+        self.local_checks += 1;
+        self.local_rule_outs += u32::from(ruled_out);
+        if self.local_checks < LOCAL_CHECK_WINDOW {
+            return false;
+        }
+        let pause = self.local_rule_outs < MIN_LOCAL_RULE_OUTS;
+        if pause {
+            self.local_pause = LOCAL_CHECK_PAUSE;
+        }
+        self.local_checks = 0;
+        self.local_rule_outs = 0;
+        pause
+    }
+
     pub(crate) fn set_var_count(&mut self, count: usize) {
         self.sat_solver.set_var_count(count);
+        self.local_solver.set_var_count(count);
+        self.assignment.set_var_count(count);
+    }
+
+    /// Returns the conflicting assignment `model`, stored in the memory of the last recycled assignment
+    /// such that conflict checks do not allocate a set per conflict.
+    fn conflicting_assignment(&mut self, model: Vec<Lit>) -> LitSet {
+        let mut assignment = std::mem::take(&mut self.assignment);
+        assignment.clear();
+        assignment.extend(model);
+        assignment
+    }
+
+    /// Keeps the memory of `assignment` for the next conflicting assignment, see [`ConflictCheck::conflicting_assignment`].
+    pub(crate) fn recycle(&mut self, assignment: LitSet) {
+        self.assignment = assignment;
+    }
+
+    /// Returns the selector of `premise` in the local solver, the selector implies that all literals
+    /// of `premise` are false. The definition is added once and kept permanently.
+    fn local_premise(&mut self, premise: Vec<Lit>) -> S::Lit {
+        if let Some(&selector) = self.premises.get(&premise) {
+            return selector;
+        }
+        let selector = self.local_solver.add_variable();
+        for &lit in &premise {
+            let lits = [!selector, self.local_solver.lookup(lit.negated())];
+            self.local_solver.add_clause(&lits);
+        }
+        self.premises.insert(premise, selector);
+        selector
+    }
+
+    /// Disables the constraints of the local check guarded by `guard`. Every [`LOCAL_SOLVER_RESET`]
+    /// retired checks, the local solver is rebuilt such that its clause database stays bounded.
+    fn retire_local_check(&mut self, guard: S::Lit, var_count: usize) {
+        self.retired_local_checks += 1;
+        if self.retired_local_checks < LOCAL_SOLVER_RESET {
+            self.local_solver.add_clause(&[!guard]);
+            return;
+        }
+        self.retired_local_checks = 0;
+        self.premises.clear();
+        self.local_solver = LookupSolver::default();
+        self.local_solver.set_var_count(var_count);
+        if let Some(trace) = &self.trace {
+            self.local_solver.set_trace(trace, "local");
+        }
     }
 
     pub(crate) fn backtrack_to(&mut self, lvl: DecLvl) {
@@ -51,47 +193,81 @@ impl<S: SatSolver> ConflictCheck<S> {
         );
     }
 
-    fn solve(&mut self, incremental_var: S::Lit) -> Option<HashSet<Lit>> {
-        if !self
-            .sat_solver
-            .solve_with_assumptions(
-                &self
-                    .assumptions
-                    .values()
-                    .copied()
-                    .chain(std::iter::once(incremental_var))
-                    .collect::<Vec<_>>(),
-            )
-            .unwrap()
-        {
-            return None;
+    fn solve(&mut self, incremental_var: S::Lit) -> Result<Option<LitSet>, S::Err> {
+        if !self.sat_solver.solve_with_assumptions(
+            &self
+                .assumptions
+                .values()
+                .copied()
+                .chain(std::iter::once(incremental_var))
+                .collect::<Vec<_>>(),
+        )? {
+            return Ok(None);
         }
-        let model = self.sat_solver.orig_model()?;
-        let model = model.into_iter().collect();
-        Some(model)
+        let Some(model) = self.sat_solver.orig_model() else {
+            return Ok(None);
+        };
+        Ok(Some(self.conflicting_assignment(model)))
     }
 }
 
 impl IncDet {
+    /// Returns the conflicting assignment if `var` is conflicted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::SatBackend`] if the SAT backend fails, it is unknown whether `var` is conflicted.
     pub(crate) fn is_conflicted(
         &mut self,
         var: Var,
         decision: Option<Lit>,
-    ) -> Option<HashSet<Lit>> {
-        // faster, incomplete check
-        trace!("local conflict check");
-        self.stats.skolem.local_conflict_checks += 1;
-        self._is_conflicted::<Varisat>(var, decision, false)?;
+    ) -> Result<Option<LitSet>, SolveError> {
+        self.check_conflict(var, decision).map_err(|err| SolveError::SatBackend(Box::new(err)))
+    }
+
+    fn check_conflict(
+        &mut self,
+        var: Var,
+        decision: Option<Lit>,
+    ) -> Result<Option<LitSet>, <Varisat as SatSolver>::Err> {
+        if self.conflict_strategy != ConflictStrategy::Global {
+            let adaptive = self.conflict_strategy == ConflictStrategy::Both;
+            if !adaptive || self.conflict_check.run_local_check() {
+                // faster, incomplete check
+                trace!("local conflict check");
+                self.stats.skolem.local_conflict_checks += 1;
+                let ruled_out = if INCREMENTAL_LOCAL_CONFLICT_CHECK {
+                    !self.is_locally_conflicted(var)?
+                } else {
+                    self._is_conflicted::<Varisat>(var, decision, false)?.is_none()
+                };
+                if adaptive && self.conflict_check.record_local_check(ruled_out) {
+                    debug!("local conflict check rarely rules out conflicts, pause it for {LOCAL_CHECK_PAUSE} checks");
+                    self.stats.skolem.local_check_pauses += 1;
+                }
+                if ruled_out {
+                    return Ok(None);
+                }
+            } else {
+                self.stats.skolem.skipped_local_conflict_checks += 1;
+            }
+        }
         // slower, complete check
         trace!("global conflict check");
         self.stats.skolem.global_conflict_checks += 1;
+        self.level_global_checks += 1;
         let assignment = if INCREMENTAL_CONFLICT_CHECK {
             self.is_conflicted_incremental(var, decision)?
         } else {
             self._is_conflicted::<Varisat>(var, decision, true)?
         };
+        let Some(assignment) = assignment else {
+            self.futile_checks[var] += 1;
+            return Ok(None);
+        };
+        self.futile_checks[var] = 0;
         self.stats.global.conflicts += 1;
-        Some(assignment)
+        Ok(Some(assignment))
     }
 
     pub(crate) fn add_definition_to_conflict_check(&mut self, lit: Lit, is_decision: bool) {
@@ -100,7 +276,8 @@ impl IncDet {
         }
         let lvl = self.trail.decision_level();
         // add definition from implication clauses
-        for cid in [lit, lit.negated()].into_iter().flat_map(|lit| self.skolem[lit].implications())
+        for cid in
+            lit.var().both_literals().into_iter().flat_map(|lit| self.skolem[lit].implications())
         {
             let clause = &self.allocator[cid];
             let sat_clause = clause
@@ -143,9 +320,9 @@ impl IncDet {
         &mut self,
         var: Var,
         decision: Option<Lit>,
-    ) -> Option<HashSet<Lit>> {
+    ) -> Result<Option<LitSet>, <Varisat as SatSolver>::Err> {
         let incremental_var = self.conflict_check.sat_solver.add_variable();
-        for lit in [Lit::positive(var), Lit::negative(var)] {
+        for lit in var.both_literals() {
             let mut build = vec![!incremental_var];
             for cid in self.skolem[lit].implications() {
                 let clause = &self.allocator[cid];
@@ -177,21 +354,25 @@ impl IncDet {
             self.conflict_check.sat_solver.add_clause(&build);
         }
         // if the formula is satisfiable, there is a conflict
-        let result = self.conflict_check.solve(incremental_var)?;
-        let assign =
-            result.iter().map(std::string::ToString::to_string).collect::<Vec<_>>().join(", ");
+        let Some(result) = self.conflict_check.solve(incremental_var)? else {
+            return Ok(None);
+        };
+        let assign = result.iter().map(|lit| lit.to_string()).collect::<Vec<_>>().join(", ");
         debug!("conflicting assignment: {}", assign);
-        Some(result)
+        Ok(Some(result))
     }
 
     fn _is_conflicted<S: SatSolver>(
-        &self,
+        &mut self,
         var: Var,
         decision: Option<Lit>,
         exact: bool,
-    ) -> Option<HashSet<Lit>> {
+    ) -> Result<Option<LitSet>, S::Err> {
         let mut solver = LookupSolver::<S>::default();
         solver.set_var_count(self.vars.get_var_count());
+        if let Some(trace) = &self.conflict_check.trace {
+            solver.set_trace(trace, if exact { "global" } else { "local" });
+        }
 
         if exact {
             // add already determined skolem functions
@@ -217,7 +398,56 @@ impl IncDet {
             }
         }
 
-        for lit in [Lit::positive(var), Lit::negative(var)] {
+        self.add_conflict_constraints(&mut solver, var, decision);
+
+        // if the formula is satisfiable, there is a conflict
+        if !solver.solve()? {
+            return Ok(None);
+        }
+        let Some(model) = solver.orig_model() else {
+            return Ok(None);
+        };
+        let result = self.conflict_check.conflicting_assignment(model);
+        let assign = result.iter().map(|lit| lit.to_string()).collect::<Vec<_>>().join(", ");
+        debug!("conflicting assignment: {}", assign);
+        Ok(Some(result))
+    }
+
+    /// Checks whether `var` is conflicted under its own implication clauses only, using the persistent
+    /// local solver of the conflict check.
+    ///
+    /// The premises of the implication clauses are encoded once (see [`ConflictCheck::local_premise`]),
+    /// every check only adds the two clauses that require an implication of `var` and one of `!var` to
+    /// apply, guarded by a fresh assumption. A decision does not need to be encoded: the default value of
+    /// a decided variable only applies if no implication of the decision literal applies, which rules out
+    /// a conflict anyway.
+    fn is_locally_conflicted(&mut self, var: Var) -> Result<bool, <Varisat as SatSolver>::Err> {
+        let guard = self.conflict_check.local_solver.add_variable();
+        for lit in var.both_literals() {
+            let mut build = vec![!guard];
+            for cid in self.skolem[lit].implications() {
+                let mut premise: Vec<Lit> =
+                    self.allocator[cid].iter().copied().filter(|&l| l != lit).collect();
+                premise.sort_unstable();
+                build.push(self.conflict_check.local_premise(premise));
+            }
+            self.conflict_check.local_solver.add_clause(&build);
+        }
+        // if the formula is satisfiable, there is a conflict
+        let is_conflicted = self.conflict_check.local_solver.solve_with_assumptions(&[guard]);
+        self.conflict_check.retire_local_check(guard, self.vars.get_var_count());
+        is_conflicted
+    }
+
+    /// Adds the constraints that are satisfiable iff both, `var` and `!var`, are implied
+    /// by the implication clauses of `var` (respectively `decision`).
+    fn add_conflict_constraints<S: SatSolver>(
+        &self,
+        solver: &mut LookupSolver<S>,
+        var: Var,
+        decision: Option<Lit>,
+    ) {
+        for lit in var.both_literals() {
             let mut build = Vec::new();
             for cid in self.skolem[lit].implications() {
                 let clause = &self.allocator[cid];
@@ -247,16 +477,66 @@ impl IncDet {
             }
             solver.add_clause(&build);
         }
+    }
+}
 
-        // if the formula is satisfiable, there is a conflict
-        if !solver.solve().unwrap() {
-            return None;
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn local_check_pause() {
+        let mut check = ConflictCheck::<Varisat>::default();
+        // a window in which the local check rules out enough conflicts
+        for idx in 0..LOCAL_CHECK_WINDOW {
+            assert!(check.run_local_check());
+            assert!(!check.record_local_check(idx < MIN_LOCAL_RULE_OUTS));
         }
-        let model = solver.orig_model()?;
-        let result: HashSet<Lit> = model.into_iter().collect();
-        let assign =
-            result.iter().map(std::string::ToString::to_string).collect::<Vec<_>>().join(", ");
-        debug!("conflicting assignment: {}", assign);
-        Some(result)
+        // a window in which the global check is needed nearly every time
+        for idx in 0..LOCAL_CHECK_WINDOW {
+            assert!(check.run_local_check());
+            let paused = check.record_local_check(idx + 1 < MIN_LOCAL_RULE_OUTS);
+            assert_eq!(paused, idx + 1 == LOCAL_CHECK_WINDOW);
+        }
+        for _ in 0..LOCAL_CHECK_PAUSE {
+            assert!(!check.run_local_check());
+        }
+        // the local check is evaluated again after the pause
+        assert!(check.run_local_check());
+    }
+
+    #[test]
+    fn local_solver_reset() {
+        let mut check = ConflictCheck::<Varisat>::default();
+        check.set_var_count(2);
+        let premise = vec![Lit::from_dimacs(-2), Lit::from_dimacs(1)];
+        let selector = check.local_premise(premise.clone());
+        assert!(selector == check.local_premise(premise.clone()));
+        for _ in 1..LOCAL_SOLVER_RESET {
+            let guard = check.local_solver.add_variable();
+            check.retire_local_check(guard, 2);
+        }
+        assert_eq!(check.premises.len(), 1);
+        // the solver is rebuilt with the last retired check
+        let guard = check.local_solver.add_variable();
+        check.retire_local_check(guard, 2);
+        assert!(check.premises.is_empty());
+        check.local_premise(premise);
+        assert_eq!(check.premises.len(), 1);
+    }
+
+    #[test]
+    fn conflicting_assignment_reuse() {
+        let mut check = ConflictCheck::<Varisat>::default();
+        check.set_var_count(100);
+        let lits =
+            |dimacs: &[i32]| dimacs.iter().copied().map(Lit::from_dimacs).collect::<Vec<_>>();
+        let first = check.conflicting_assignment(lits(&[1, -2, 100]));
+        assert_eq!(first.iter().collect::<Vec<_>>(), lits(&[1, -2, 100]));
+        check.recycle(first);
+
+        // the next assignment is stored in the recycled set, without the literals of the previous one
+        let second = check.conflicting_assignment(lits(&[-1, 3]));
+        assert_eq!(second.iter().collect::<Vec<_>>(), lits(&[-1, 3]));
     }
 }
@@ -0,0 +1,107 @@
+//! Counting the Skolem functions of tiny 2QBF instances, e.g., to illustrate the solution space.
+//!
+//! For a formula `∀X ∃Y. φ`, the Skolem functions for `Y` can be chosen independently for every
+//! assignment `x` of `X`, thus, their number is the product of the number of models of `φ[x]`.
+//! The models are enumerated by a SAT solver that blocks every found model with a clause.
+
+use super::IncDet;
+use crate::{
+    literal::Var,
+    qcnf::QCNF,
+    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    QuantTy,
+};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, warn};
+
+/// The maximal number of variables, there are at most `2^MAX_COUNT_VARIABLES` models to enumerate.
+const MAX_COUNT_VARIABLES: usize = 16;
+
+impl IncDet {
+    /// Counts the distinct Skolem functions of the existential variables of `qcnf`,
+    /// i.e., the number of winning strategies of the existential player.
+    /// Two Skolem functions are distinct if they differ for some universal assignment.
+    /// For formulas without universal variables, this is the number of models.
+    ///
+    /// Returns `None` if the formula is not of the form `∀X ∃Y`, contains variables that are not
+    /// bound by the prefix, has more than 16 variables, or the count exceeds `u64`.
+    #[must_use]
+    pub fn count_models(qcnf: &QCNF) -> Option<u64> {
+        let scopes = qcnf.prefix.iter().skip_while(|(quant, _)| *quant == QuantTy::Forall);
+        if scopes.clone().any(|(quant, _)| *quant == QuantTy::Forall) {
+            warn!("Counting Skolem functions requires a prefix of the form ∀X ∃Y");
+            return None;
+        }
+        let universals: Vec<Var> = qcnf
+            .prefix
+            .iter()
+            .take_while(|(quant, _)| *quant == QuantTy::Forall)
+            .flat_map(|(_, vars)| vars.iter().copied())
+            .collect();
+        let existentials: Vec<Var> = scopes.flat_map(|(_, vars)| vars.iter().copied()).collect();
+        if universals.len() + existentials.len() > MAX_COUNT_VARIABLES {
+            warn!("Formula is too large to count Skolem functions");
+            return None;
+        }
+        let universal_index: HashMap<Var, usize> =
+            universals.iter().enumerate().map(|(idx, &var)| (var, idx)).collect();
+        if qcnf.matrix.iter().flatten().any(|lit| {
+            !universal_index.contains_key(&lit.var()) && !existentials.contains(&lit.var())
+        }) {
+            warn!("Counting Skolem functions requires all variables to be bound");
+            return None;
+        }
+
+        let mut count: u64 = 1;
+        for assignment in 0_u32..(1 << universals.len()) {
+            let models = count_models_under(qcnf, &existentials, |var| {
+                universal_index.get(&var).map(|&idx| assignment & (1 << idx) != 0)
+            });
+            debug!("universal assignment {assignment:#b} has {models} models");
+            count = count.checked_mul(models)?;
+        }
+        Some(count)
+    }
+}
+
+/// Enumerates the assignments of `existentials` that satisfy the matrix of `qcnf`
+/// under the universal assignment `value`.
+fn count_models_under(
+    qcnf: &QCNF,
+    existentials: &[Var],
+    value: impl Fn(Var) -> Option<bool>,
+) -> u64 {
+    let mut solver = LookupSolver::<Varisat>::default();
+    solver.set_var_count(qcnf.num_variables().try_into().unwrap());
+    let mut constrained = HashSet::new();
+    let mut clause = Vec::new();
+    for lits in &qcnf.matrix {
+        if lits.iter().any(|lit| value(lit.var()) == Some(lit.polarity())) {
+            continue;
+        }
+        clause.clear();
+        for &lit in lits.iter().filter(|lit| value(lit.var()).is_none()) {
+            constrained.insert(lit.var());
+            clause.push(solver.lookup(lit));
+        }
+        if clause.is_empty() {
+            // clause is falsified by the universal assignment
+            return 0;
+        }
+        solver.add_clause(&clause);
+    }
+    let mut models = 0;
+    while solver.solve().unwrap() {
+        models += 1;
+        let model = solver.orig_model().unwrap();
+        let blocking: Vec<_> = model.into_iter().map(|lit| solver.lookup(lit.negated())).collect();
+        if blocking.is_empty() {
+            // the only model is the empty assignment
+            break;
+        }
+        solver.add_clause(&blocking);
+    }
+    // the solver does not know the unconstrained existentials, each of them doubles the number of models
+    let unconstrained = existentials.iter().filter(|var| !constrained.contains(var)).count();
+    models << unconstrained
+}
@@ -0,0 +1,176 @@
+//! Lazy enumeration of the universal assignments refuting a 2QBF `∀X ∃Y. φ`.
+//!
+//! An assignment `x` of `X` refutes the formula if `φ[x]` is unsatisfiable.
+//! The assignments are found by counterexample guided abstraction refinement:
+//! a candidate solver proposes assignments `x`, a matrix solver checks `φ` under the assumptions `x`.
+//! If `φ[x]` has a model `y`, every candidate has to falsify one of the clauses not satisfied by `y`.
+//! Otherwise, `x` refutes the formula and is blocked in the candidate solver.
+
+use super::IncDet;
+use crate::{
+    literal::{Lit, Var},
+    sat::{varisat::Varisat, LookupSolver, SatSolver},
+    QuantTy,
+};
+use std::collections::HashSet;
+use tracing::{debug, error};
+
+type SatLit = <Varisat as SatSolver>::Lit;
+
+/// Iterator over the universal assignments refuting a formula, see [`IncDet::counterexamples`].
+#[derive(Debug)]
+pub struct Counterexamples {
+    /// sorted by variable
+    universals: Vec<Var>,
+    clauses: Vec<Vec<Lit>>,
+    /// proposes universal assignments, contains a variable per clause that implies it is falsified
+    candidates: LookupSolver<Varisat>,
+    falsified: Vec<SatLit>,
+    /// contains the clauses, checks whether a candidate can be satisfied
+    matrix: LookupSolver<Varisat>,
+    exhausted: bool,
+}
+
+impl IncDet {
+    /// Returns an iterator over the distinct assignments of the universal variables under which
+    /// the matrix has no satisfying assignment of the existential variables, i.e., the formula
+    /// `∀X ∃Y. φ` is false. The formula is true iff the iterator is empty.
+    ///
+    /// The assignments are computed lazily, every call to `next` solves a sequence of SAT problems
+    /// over the stored clauses, which include the learnt clauses.
+    /// The number of refuting assignments may be exponential in the number of universal variables,
+    /// thus, use [`Iterator::take`] to bound the enumeration.
+    /// Every assignment contains a literal for every universal variable, sorted by variable.
+    ///
+    /// Only formulas of the form `∀X ∃Y` are supported, the iterator is empty for other prefixes.
+    #[must_use]
+    pub fn counterexamples(&self) -> Counterexamples {
+        let mut universals: Vec<Var> = self
+            .prefix
+            .iter()
+            .filter(|scope| scope.quantifier == QuantTy::Forall)
+            .flat_map(|scope| scope.variables.iter().copied())
+            .collect();
+        universals.sort_unstable();
+        let clauses = self.allocator.iter().map(|(_, clause)| clause.lits().to_vec()).collect();
+        let supported = self
+            .prefix
+            .iter()
+            .skip_while(|scope| scope.quantifier == QuantTy::Forall)
+            .all(|scope| scope.quantifier == QuantTy::Exists);
+        if !supported {
+            error!("Counterexamples require a prefix of the form ∀X ∃Y");
+        }
+        Counterexamples::new(universals, clauses, self.vars.get_var_count(), !supported)
+    }
+}
+
+impl Counterexamples {
+    fn new(
+        universals: Vec<Var>,
+        clauses: Vec<Vec<Lit>>,
+        var_count: usize,
+        exhausted: bool,
+    ) -> Self {
+        let mut candidates = LookupSolver::<Varisat>::default();
+        candidates.set_var_count(var_count);
+        let mut matrix = LookupSolver::<Varisat>::default();
+        matrix.set_var_count(var_count);
+        let universal_set: HashSet<Var> = universals.iter().copied().collect();
+        let mut falsified = Vec::with_capacity(clauses.len());
+        for clause in &clauses {
+            let mapped: Vec<_> = clause.iter().map(|&lit| matrix.lookup(lit)).collect();
+            matrix.add_clause(&mapped);
+
+            // the clause is falsified if all universal literals are false
+            let is_falsified = candidates.add_variable();
+            for &lit in clause.iter().filter(|lit| universal_set.contains(&lit.var())) {
+                let lit = candidates.lookup(lit);
+                candidates.add_clause(&[!is_falsified, !lit]);
+            }
+            falsified.push(is_falsified);
+        }
+        Self { universals, clauses, candidates, falsified, matrix, exhausted }
+    }
+
+    /// Returns the next candidate assignment of the universal variables, `None` if there is none.
+    fn next_candidate(&mut self) -> Option<Vec<Lit>> {
+        match self.candidates.solve() {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(err) => {
+                error!("SAT solver failed: {err}");
+                return None;
+            }
+        }
+        let model: HashSet<Lit> = self.candidates.orig_model()?.into_iter().collect();
+        // universals not constrained by the candidate solver are assigned false
+        Some(
+            self.universals
+                .iter()
+                .map(|&var| {
+                    if model.contains(&Lit::positive(var)) {
+                        Lit::positive(var)
+                    } else {
+                        Lit::negative(var)
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Iterator for Counterexamples {
+    type Item = Vec<Lit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.exhausted {
+            let Some(candidate) = self.next_candidate() else {
+                self.exhausted = true;
+                break;
+            };
+            let assumptions: Vec<_> =
+                candidate.iter().map(|&lit| self.matrix.lookup(lit)).collect();
+            match self.matrix.solve_with_assumptions(&assumptions) {
+                Ok(true) => {}
+                Ok(false) => {
+                    debug!("counterexample {candidate:?}");
+                    let blocking: Vec<_> =
+                        candidate.iter().map(|&lit| self.candidates.lookup(!lit)).collect();
+                    if blocking.is_empty() {
+                        // the only universal assignment is the empty one
+                        self.exhausted = true;
+                    } else {
+                        self.candidates.add_clause(&blocking);
+                    }
+                    return Some(candidate);
+                }
+                Err(err) => {
+                    error!("SAT solver failed: {err}");
+                    self.exhausted = true;
+                    break;
+                }
+            }
+            // refine: the next candidate has to falsify a clause that is not satisfied by the model
+            let model: HashSet<Lit> = self.matrix.orig_model().unwrap().into_iter().collect();
+            let refinement: Vec<_> = self
+                .clauses
+                .iter()
+                .zip(&self.falsified)
+                .filter(|(clause, _)| {
+                    !clause.iter().any(|lit| {
+                        self.universals.binary_search(&lit.var()).is_err() && model.contains(lit)
+                    })
+                })
+                .map(|(_, &is_falsified)| is_falsified)
+                .collect();
+            if refinement.is_empty() {
+                // the model satisfies the matrix for every universal assignment
+                self.exhausted = true;
+            } else {
+                self.candidates.add_clause(&refinement);
+            }
+        }
+        None
+    }
+}
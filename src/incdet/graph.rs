@@ -7,18 +7,20 @@ use crate::{
     },
     datastructure::LitVec,
     incdet::propagation::trail::DecLvl,
-    literal::Lit,
 };
 
 pub(crate) type ImplGraph = LitVec<Vec<Impl>>;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct Impl {
-    pub(crate) lit: Lit,
     pub(crate) clause: ClauseId,
     pub(crate) dec_lvl: DecLvl,
 }
 
+// the graph stores an entry per implication, thus, clause ids and decision levels are 32 bit
+#[cfg(not(kani))]
+const _: () = assert!(core::mem::size_of::<Impl>() <= 8);
+
 impl ImplGraph {
     pub(crate) fn backtrack_to(&mut self, lvl: DecLvl) {
         self.iter_mut().for_each(|imps| {
@@ -28,7 +30,7 @@ impl ImplGraph {
 }
 
 impl Impl {
-    pub(crate) fn reason<'alloc>(&self, allocator: &'alloc Allocator) -> &'alloc Clause {
+    pub(crate) fn reason(self, allocator: &Allocator) -> &Clause {
         &allocator[self.clause]
     }
 }
@@ -16,18 +16,23 @@ pub(crate) enum Value {
     NegativeImplications,
 }
 
+/// A copy of an [`Assignment`], see [`Assignment::snapshot`].
+#[derive(Debug, Clone)]
+pub(crate) struct AssignmentSnapshot {
+    assignment: VarVec<Option<Value>>,
+}
+
 impl Assignment {
     pub(crate) fn set_var_count(&mut self, count: usize) {
         self.assignment.set_var_count(count);
     }
 
     pub(crate) fn assign_constant(&mut self, lit: Lit) {
-        self.assignment[lit.var()] =
-            Some(if lit.is_positive() { Value::True } else { Value::False });
+        self.assignment[lit.var()] = Some(if lit.polarity() { Value::True } else { Value::False });
     }
 
     pub(crate) fn assign_function(&mut self, lit: Lit) {
-        self.assignment[lit.var()] = Some(if lit.is_positive() {
+        self.assignment[lit.var()] = Some(if lit.polarity() {
             Value::PositiveImplications
         } else {
             Value::NegativeImplications
@@ -42,6 +47,22 @@ impl Assignment {
     pub(crate) fn is_assigned(&self, var: Var) -> bool {
         self.assignment[var].is_some()
     }
+
+    /// Returns a copy of the current assignment, e.g., to tentatively assign and propagate
+    /// literals for look-ahead without going through the trail, see [`Assignment::restore`].
+    #[allow(dead_code)]
+    pub(crate) fn snapshot(&self) -> AssignmentSnapshot {
+        AssignmentSnapshot { assignment: self.assignment.clone() }
+    }
+
+    /// Restores the assignment of `snapshot`, the same snapshot may be restored multiple times.
+    /// Variables added after the snapshot was taken are unassigned.
+    #[allow(dead_code)]
+    pub(crate) fn restore(&mut self, snapshot: &AssignmentSnapshot) {
+        let count = self.assignment.get_var_count();
+        self.assignment.clone_from(&snapshot.assignment);
+        self.assignment.set_var_count(count);
+    }
 }
 
 impl std::ops::Index<Var> for Assignment {
@@ -71,4 +92,31 @@ mod test {
         *assignment[var1].get_or_insert(Value::False) = Value::True;
         assert_eq!(assignment[var1], Some(Value::True));
     }
+
+    #[test]
+    fn snapshot() {
+        let mut assignment = Assignment::default();
+        assignment.set_var_count(3);
+        let [var1, var2, var3] = [1, 2, 3].map(Var::from_dimacs);
+        assignment.assign_constant(Lit::positive(var1));
+        let snapshot = assignment.snapshot();
+
+        for lit in [Lit::negative(var2), Lit::positive(var2)] {
+            assignment.assign_function(lit);
+            assignment.assign_constant(Lit::negative(var3));
+            assignment.unassign(var1);
+            assignment.restore(&snapshot);
+            assert_eq!(assignment[var1], Some(Value::True));
+            assert!(!assignment.is_assigned(var2));
+            assert!(!assignment.is_assigned(var3));
+        }
+
+        // variables added after the snapshot are unassigned
+        assignment.set_var_count(4);
+        let var4 = Var::from_dimacs(4);
+        assignment.assign_constant(Lit::positive(var4));
+        assignment.restore(&snapshot);
+        assert!(!assignment.is_assigned(var4));
+        assert_eq!(assignment[var1], Some(Value::True));
+    }
 }
@@ -8,8 +8,9 @@ pub(crate) struct Trail {
     decisions: Vec<usize>,
 }
 
+/// A decision level of the search, the root level precedes the first decision.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct DecLvl(usize);
+pub(crate) struct DecLvl(u32);
 
 impl Trail {
     pub(crate) fn push(&mut self, lit: Lit) {
@@ -17,7 +18,7 @@ impl Trail {
     }
 
     pub(crate) fn decision_level(&self) -> DecLvl {
-        DecLvl(self.decisions.len())
+        DecLvl::from_index(self.decisions.len())
     }
 
     pub(crate) fn add_decision(&mut self, lit: Lit) {
@@ -34,6 +35,28 @@ impl Trail {
         self.decisions.iter().map(|&idx| &self.trail[idx])
     }
 
+    /// Returns the literals assigned at decision level `lvl`, starting with the decision literal.
+    /// The literals at the root level are assigned without a decision.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lvl` is above the current decision level.
+    pub(crate) fn assignments_at(&self, lvl: DecLvl) -> &[Lit] {
+        assert!(lvl <= self.decision_level(), "decision level {lvl} is not on the trail");
+        let start = if lvl.is_root() { 0 } else { self.decisions[lvl.level() - 1] };
+        let end = self.decisions.get(lvl.level()).copied().unwrap_or(self.trail.len());
+        &self.trail[start..end]
+    }
+
+    /// Returns the decision levels from the root level upwards together with their assigned literals,
+    /// see [`Trail::assignments_at`].
+    pub(crate) fn levels(&self) -> impl Iterator<Item = (DecLvl, &[Lit])> {
+        (0..=self.decisions.len()).map(|idx| {
+            let lvl = DecLvl::from_index(idx);
+            (lvl, self.assignments_at(lvl))
+        })
+    }
+
     pub(crate) fn is_decision(&self, lit: Lit) -> bool {
         self.iter_decisions().any(|&l| l == lit)
     }
@@ -42,8 +65,8 @@ impl Trail {
     where
         F: FnMut(Lit),
     {
-        let trail_idx = self.decisions[lvl.0];
-        self.decisions.truncate(lvl.0);
+        let trail_idx = self.decisions[lvl.level()];
+        self.decisions.truncate(lvl.level());
         self.trail[trail_idx..].iter().copied().rev().for_each(callback);
         self.trail.truncate(trail_idx);
     }
@@ -54,19 +77,76 @@ impl Trail {
 }
 
 impl DecLvl {
+    /// The level of the assignments that do not depend on a decision.
     pub(crate) const ROOT: DecLvl = DecLvl(0);
 
     pub(crate) fn is_root(self) -> bool {
         self == Self::ROOT
     }
 
+    /// # Panics
+    ///
+    /// Panics if `idx` exceeds `u32::MAX`, which cannot happen as there is at most one decision per variable.
+    fn from_index(idx: usize) -> Self {
+        Self(u32::try_from(idx).expect("decision level exceeds u32::MAX"))
+    }
+
+    /// Returns the number of decisions up to this level, the root level is 0.
+    pub(crate) fn level(self) -> usize {
+        usize::try_from(self.0).unwrap()
+    }
+
     pub(crate) fn successor(self) -> Self {
         Self(self.0 + 1)
     }
 }
 
+impl From<DecLvl> for u32 {
+    fn from(lvl: DecLvl) -> Self {
+        lvl.0
+    }
+}
+
 impl std::fmt::Display for DecLvl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::literal::Var;
+
+    #[test]
+    fn levels() {
+        let [lit1, lit2, lit3, lit4, lit5] =
+            [1, 2, 3, 4, 5].map(|v| Lit::positive(Var::from_dimacs(v)));
+        let mut trail = Trail::default();
+        assert_eq!(trail.levels().collect::<Vec<_>>(), vec![(DecLvl::ROOT, &[][..])]);
+
+        trail.push(lit1);
+        trail.add_decision(lit2);
+        trail.push(lit3);
+        trail.add_decision(lit4);
+        trail.add_decision(lit5);
+        assert_eq!(trail.assignments_at(DecLvl::ROOT), &[lit1]);
+        assert_eq!(trail.assignments_at(DecLvl(1)), &[lit2, lit3]);
+        assert_eq!(
+            trail.levels().collect::<Vec<_>>(),
+            vec![
+                (DecLvl::ROOT, &[lit1][..]),
+                (DecLvl(1), &[lit2, lit3][..]),
+                (DecLvl(2), &[lit4][..]),
+                (DecLvl(3), &[lit5][..]),
+            ]
+        );
+        let lits: Vec<Lit> = trail.levels().flat_map(|(_, lits)| lits.iter().copied()).collect();
+        assert_eq!(lits, trail.iter().copied().collect::<Vec<_>>());
+
+        trail.backtrack_to(DecLvl(1), |_| {});
+        assert_eq!(trail.levels().count(), 2);
+        assert_eq!(trail.assignments_at(DecLvl::ROOT), &[lit1]);
+        assert_eq!(trail.assignments_at(DecLvl(1)), &[lit2, lit3]);
+    }
+}
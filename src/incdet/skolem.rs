@@ -1,7 +1,8 @@
 use crate::{
-    clause::alloc::{Allocator, ClauseId},
+    clause::alloc::ClauseId,
     datastructure::LitVec,
     incdet::propagation::trail::DecLvl,
+    literal::{Lit, Var},
 };
 use std::collections::BTreeMap;
 
@@ -11,11 +12,18 @@ pub(crate) type Skolem = LitVec<Implications>;
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Implications {
     implications: BTreeMap<DecLvl, Vec<ClauseId>>,
+    /// the number of literals of the implication clauses added at a decision level
+    lit_counts: BTreeMap<DecLvl, usize>,
+    /// the number of literals of all implication clauses, see [`Implications::lit_count`]
+    lit_count: usize,
 }
 
 impl Implications {
-    pub(crate) fn add_implication(&mut self, clause_id: ClauseId, lvl: DecLvl) {
+    /// Adds the implication clause `clause_id` with `len` literals at decision level `lvl`.
+    pub(crate) fn add_implication(&mut self, clause_id: ClauseId, len: usize, lvl: DecLvl) {
         self.implications.entry(lvl).or_default().push(clause_id);
+        *self.lit_counts.entry(lvl).or_default() += len;
+        self.lit_count += len;
     }
 
     pub(crate) fn implications(&self) -> impl Iterator<Item = ClauseId> + '_ {
@@ -26,13 +34,21 @@ impl Implications {
         self.implications.values().map(Vec::len).sum()
     }
 
-    pub(crate) fn lit_count(&self, alloc: &Allocator) -> usize {
-        self.implications().map(|c| alloc[c].lits().len()).sum()
+    /// Returns the approximate number of bytes used by the implications.
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.len() * std::mem::size_of::<ClauseId>()
+    }
+
+    /// Returns the total number of literals of the implication clauses, it is maintained incrementally.
+    pub(crate) fn lit_count(&self) -> usize {
+        self.lit_count
     }
 
     fn backtrack_to(&mut self, lvl: DecLvl) {
         // backtrackign to `lvl` means that we keep all entries with level <= `lvl`
         self.implications.split_off(&lvl.successor());
+        let removed = self.lit_counts.split_off(&lvl.successor());
+        self.lit_count -= removed.values().sum::<usize>();
     }
 }
 
@@ -41,3 +57,55 @@ impl Skolem {
         self.iter_mut().for_each(|imp| imp.backtrack_to(lvl));
     }
 }
+
+/// Structured representation of the Skolem function of a single existential variable,
+/// obtained from its implication clauses by [`crate::incdet::IncDet::model_function`].
+///
+/// Every implication clause `l_1 ∨ … ∨ l_k ∨ x` (respectively `¬x`) is a case with condition
+/// `¬l_1 ∧ … ∧ ¬l_k` and value `true` (respectively `false`).
+/// A decided literal holds iff one of its implications applies, thus, the function of a decided variable
+/// ends with the opposite value under the empty condition.
+/// The conditions range over the variables the function depends on, i.e., universal variables and,
+/// possibly, existential variables whose functions have to be evaluated first.
+///
+/// The function is evaluated under an assignment by returning the value of the first case whose condition
+/// is satisfied. For a Skolem function of a solved formula, the implication cases of different values
+/// are never satisfied at the same time, the fallback of a decided variable only applies if no implication does.
+/// If no condition is satisfied, the function is partial, [`crate::verify_skolem`] uses the value `false`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkolemFn {
+    var: Var,
+    cases: Vec<(Vec<Lit>, bool)>,
+}
+
+impl SkolemFn {
+    /// Creates the function of `var` from pairs of a condition, a conjunction of literals, and the value.
+    #[must_use]
+    pub fn new(var: Var, cases: Vec<(Vec<Lit>, bool)>) -> Self {
+        Self { var, cases }
+    }
+
+    /// The existential variable defined by this function.
+    #[must_use]
+    pub fn var(&self) -> Var {
+        self.var
+    }
+
+    /// The cases of the function as pairs of a condition, a conjunction of literals, and the value.
+    /// For functions obtained by [`crate::incdet::IncDet::model_function`], the cases implying the value `true` come first,
+    /// followed by the cases implying `false` and the fallback of a decided variable.
+    #[must_use]
+    pub fn cases(&self) -> &[(Vec<Lit>, bool)] {
+        &self.cases
+    }
+
+    /// Evaluates the function under `assignment`, i.e., a set of literals.
+    /// Returns `None` if no condition is satisfied, i.e., the value can be chosen arbitrarily.
+    #[must_use]
+    pub fn eval(&self, assignment: &[Lit]) -> Option<bool> {
+        self.cases
+            .iter()
+            .find(|(condition, _)| condition.iter().all(|lit| assignment.contains(lit)))
+            .map(|&(_, value)| value)
+    }
+}
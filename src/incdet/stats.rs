@@ -1,4 +1,31 @@
-use std::time::Duration;
+use crate::literal::Lit;
+use std::time::{Duration, Instant};
+
+/// A snapshot of the solver progress, see [`super::IncDet::solve_with_callback`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverProgress {
+    pub decisions: u32,
+    pub conflicts: u32,
+    pub added_clauses: u32,
+    pub decision_level: usize,
+    /// the number of deterministic variables
+    pub assigned: usize,
+}
+
+/// The distribution of the watch-list lengths, see [`super::IncDet::watch_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WatchStats {
+    /// the total number of watches
+    pub watches: usize,
+    /// the total number of watches of clauses with exactly two existential literals, see [`WatchStats::watches`] for the others
+    pub binary_watches: usize,
+    /// the length of the longest watch list
+    pub max_len: usize,
+    /// the mean watch-list length over all literals
+    pub mean_len: f64,
+    /// the literal with the longest watch list, `None` if there are no watches
+    pub max_lit: Option<Lit>,
+}
 
 #[derive(Debug, Default)]
 pub(crate) struct Statistics {
@@ -11,6 +38,17 @@ pub(crate) struct GlobalStats {
     pub(crate) decisions: u32,
     pub(crate) conflicts: u32,
     pub(crate) added_clauses: u32,
+    /// the number of redundant literals removed from learnt clauses by minimization
+    pub(crate) minimized_literals: u32,
+    pub(crate) strengthened_literals: u32,
+    /// the number of times a clause was used as reason in conflict analysis
+    pub(crate) clause_bumps: u32,
+    /// the number of learnt clauses exceeding the size limit
+    pub(crate) oversized_learnt_clauses: u32,
+    /// the number of compactions by the solve loop to remove oversized learnt clauses
+    pub(crate) learnt_compactions: u32,
+    /// the number of learnt clauses that were not added, as an equal clause was already an implication
+    pub(crate) redundant_learnt_clauses: u32,
     pub(crate) solve_time: Duration,
 }
 
@@ -18,7 +56,56 @@ pub(crate) struct GlobalStats {
 pub(crate) struct SkolemStats {
     pub(crate) local_det_checks: u32,
     pub(crate) local_conflict_checks: u32,
+    /// the number of conflict checks that skipped the local check, as it was paused
+    pub(crate) skipped_local_conflict_checks: u32,
+    /// the number of times the local check was paused, as it rarely ruled out a conflict
+    pub(crate) local_check_pauses: u32,
     pub(crate) global_conflict_checks: u32,
+    /// the number of times propagation paused, as the global conflict checks reached the limit of the decision level
+    pub(crate) deferred_propagations: u32,
     pub(crate) function_propagations: u32,
+    /// the number of implications derived from clauses with exactly two existential literals
+    pub(crate) binary_implications: u32,
     pub(crate) constant_propagations: u32,
 }
+
+/// Emits periodic progress lines with the conflict and decision rates since the previous line,
+/// see [`super::IncDet::with_progress_interval`].
+#[derive(Debug)]
+pub(crate) struct Heartbeat {
+    interval: Duration,
+    last: Instant,
+    conflicts: u32,
+    decisions: u32,
+}
+
+impl Heartbeat {
+    pub(crate) fn new(interval: Duration, progress: &SolverProgress) -> Self {
+        Self {
+            interval,
+            last: Instant::now(),
+            conflicts: progress.conflicts,
+            decisions: progress.decisions,
+        }
+    }
+
+    pub(crate) fn is_due(&self) -> bool {
+        self.last.elapsed() >= self.interval
+    }
+
+    /// Returns the progress line and starts the next interval.
+    pub(crate) fn report(&mut self, progress: &SolverProgress) -> String {
+        let now = Instant::now();
+        // avoids a division by zero for the empty interval
+        let secs = now.duration_since(self.last).as_secs_f64().max(f64::EPSILON);
+        let conflict_rate = f64::from(progress.conflicts - self.conflicts) / secs;
+        let decision_rate = f64::from(progress.decisions - self.decisions) / secs;
+        self.last = now;
+        self.conflicts = progress.conflicts;
+        self.decisions = progress.decisions;
+        format!(
+            "c {} conflicts, {conflict_rate:.0} conflicts/s, {decision_rate:.0} decisions/s, level {}",
+            progress.conflicts, progress.decision_level
+        )
+    }
+}
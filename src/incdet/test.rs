@@ -1,4 +1,21 @@
-use crate::{incdet::IncDet, SolverResult};
+use crate::{
+    incdet::{
+        propagation::trail::DecLvl, stats::Heartbeat, Budget, ConflictStrategy, IncDet,
+        LiteralOrder, SolverProgress,
+    },
+    literal::{Lit, Var},
+    QuantTy, SolveError, SolverResult,
+};
+use proptest::prelude::*;
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 #[test]
 fn propagation_sat() {
@@ -55,9 +72,9 @@ fn global_conflict_analysis() {
     assert_eq!(solver.solve(), SolverResult::Satisfiable);
 }
 
-#[test]
-fn unsat_with_decsision() {
-    let qcnf = qcnf_formula![
+/// An unsatisfiable 2QBF that is only refuted after a decision, shared by the tests of the solver options.
+fn two_level_unsat() -> crate::qcnf::QCNF {
+    qcnf_formula![
         a 1 2;
         e 3 4 5;
         2 -3;
@@ -67,9 +84,50 @@ fn unsat_with_decsision() {
         1 3 4;
         -1 5;
         1 -5;
-    ];
+    ]
+}
+
+#[test]
+fn unsat_with_decsision() {
+    let qcnf = two_level_unsat();
     let mut solver = IncDet::from_qcnf(&qcnf);
     assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+    assert!(solver.stats.global.decisions > 0);
+}
+
+#[test]
+fn budget() {
+    let qcnf = two_level_unsat();
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let budget = Budget { conflicts: Some(0), decisions: None };
+    assert_eq!(solver.solve_with_budget(budget), SolverResult::Unknown);
+    assert_eq!(solver.stats.global.conflicts, 0);
+
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let budget = Budget { conflicts: None, decisions: Some(0) };
+    assert_eq!(solver.solve_with_budget(budget), SolverResult::Unknown);
+    assert_eq!(solver.stats.global.decisions, 0);
+
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let budget = Budget { conflicts: Some(100), decisions: Some(100) };
+    assert_eq!(solver.solve_with_budget(budget), SolverResult::Unsatisfiable);
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve_with_budget(Budget::default()), SolverResult::Unsatisfiable);
+}
+
+#[test]
+fn memory_limit() {
+    let qcnf = two_level_unsat();
+    let unlimited = IncDet::from_qcnf(&qcnf);
+    assert!(unlimited.memory_usage() > 0);
+
+    // the limit is already exceeded by the input clauses
+    let mut solver = IncDet::builder().memory_limit(0).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Unknown);
+    assert_eq!(solver.stats.global.conflicts, 1);
+
+    let mut solver = IncDet::from_qcnf(&qcnf).with_memory_limit(usize::MAX);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
 }
 
 #[test]
@@ -101,6 +159,46 @@ fn unsat_2() {
     assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
 }
 
+#[test]
+fn conflict_strategy() {
+    let formulas = [
+        qcnf_formula![
+            a 1;
+            e 2 3;
+            2;
+            2 -3;
+            -2 3;
+            2 3;
+        ],
+        two_level_unsat(),
+    ];
+    for qcnf in formulas {
+        let expected = IncDet::from_qcnf(&qcnf).solve();
+        let mut solver =
+            IncDet::builder().conflict_strategy(ConflictStrategy::Global).build_from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), expected);
+        assert_eq!(solver.stats.skolem.local_conflict_checks, 0);
+        assert!(solver.stats.skolem.global_conflict_checks > 0);
+
+        let mut solver =
+            IncDet::from_qcnf(&qcnf).with_conflict_strategy(ConflictStrategy::AlwaysLocal);
+        assert_eq!(solver.solve(), expected);
+        assert!(solver.stats.skolem.local_conflict_checks > 0);
+        assert_eq!(solver.stats.skolem.skipped_local_conflict_checks, 0);
+    }
+}
+
+proptest! {
+    #[test]
+    fn conflict_strategies_agree(qcnf in crate::qcnf::strategy::qcnf(2..=2, 1..6, 0..16, 1..4)) {
+        let expected = IncDet::from_qcnf(&qcnf).solve();
+        for strategy in [ConflictStrategy::AlwaysLocal, ConflictStrategy::Global] {
+            let mut solver = IncDet::from_qcnf(&qcnf).with_conflict_strategy(strategy);
+            prop_assert_eq!(solver.solve(), expected);
+        }
+    }
+}
+
 #[test]
 fn constant_propagation_unsat() {
     let qcnf = qcnf_formula![
@@ -112,3 +210,1328 @@ fn constant_propagation_unsat() {
     let mut solver = IncDet::from_qcnf(&qcnf);
     assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
 }
+
+#[test]
+fn prefix() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        a 4;
+        e 5;
+        -3 5;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    let prefix: Vec<_> = solver.prefix().collect();
+    assert_eq!(
+        prefix,
+        [
+            (QuantTy::Forall, &[Var::from_dimacs(1), Var::from_dimacs(2)][..]),
+            (QuantTy::Exists, &[Var::from_dimacs(3)][..]),
+            (QuantTy::Forall, &[Var::from_dimacs(4)][..]),
+            (QuantTy::Exists, &[Var::from_dimacs(5)][..]),
+        ]
+    );
+}
+
+#[test]
+fn clause_handle() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        2 3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let first = solver.add_input_clause(&lits(&[2, -1])).unwrap();
+    let second = solver.add_input_clause(&lits(&[-2, 1])).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(solver.clause(first), lits(&[-1, 2]));
+    assert_eq!(solver.clause(second), lits(&[1, -2]));
+    // tautologies are not stored
+    assert_eq!(solver.add_input_clause(&lits(&[2, -2])), None);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn add_clauses() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let clauses = [lits(&[1, -2]), lits(&[2, -2]), lits(&[-1, 2])];
+    let clauses: Vec<&[Lit]> = clauses.iter().map(Vec::as_slice).collect();
+    let mut solver = IncDet::default();
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(1)]);
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(2)]);
+    let handles = solver.add_clauses(&clauses);
+    assert_eq!(handles.len(), 3);
+    // tautologies are not stored
+    assert_eq!(handles[1], None);
+    assert_eq!(solver.clause(handles[0].unwrap()), clauses[0]);
+    assert_eq!(solver.clause(handles[2].unwrap()), clauses[2]);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn clause_activity() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4 5 6 7;
+        2 -3;
+        -1 -2 3;
+        1 -4;
+        -3 -4;
+        1 3 4;
+        -1 5;
+        1 -5;
+        6 7;
+    ];
+    let mut solver = IncDet::default();
+    for (quant, vars) in &qcnf.prefix {
+        solver._quantify(*quant, vars);
+    }
+    let clauses: Vec<&[Lit]> = qcnf.matrix.iter().map(Vec::as_slice).collect();
+    let handles: Vec<_> = solver.add_clauses(&clauses).into_iter().map(Option::unwrap).collect();
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+    assert!(solver.stats.global.clause_bumps > 0);
+    // the clause over 6 and 7 is independent of the conflicts
+    let (&unused, used) = handles.split_last().unwrap();
+    assert!(solver.clause_activity(unused) <= 0.0);
+    assert!(used.iter().any(|&handle| solver.clause_activity(handle) > 0.0));
+}
+
+#[test]
+fn skolem_clauses() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        1 -2;
+        -1 2;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let (positive, negative) = solver.skolem_clauses(Var::from_dimacs(2));
+    assert_eq!(positive, [lits(&[-1, 2])]);
+    assert_eq!(negative, [lits(&[1, -2])]);
+    assert_eq!(solver.skolem_clauses(Var::from_dimacs(1)), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn model_function() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        // 3 <=> 1 & 2
+        1 -3; 2 -3; -1 -2 3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    let lits = |lits: &[i32]| lits.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let function = solver.model_function(Var::from_dimacs(3));
+    assert_eq!(function.var(), Var::from_dimacs(3));
+    assert_eq!(function.cases()[0], (lits(&[1, 2]), true));
+    assert_eq!(function.cases().len(), 3);
+    for (x, y) in [(1, 2), (1, -2), (-1, 2), (-1, -2)] {
+        assert_eq!(function.eval(&lits(&[x, y])), Some(x > 0 && y > 0));
+    }
+    assert_eq!(function.eval(&[]), None);
+    assert!(solver.model_function(Var::from_dimacs(1)).cases().is_empty());
+    // beyond the variable count
+    assert!(solver.model_function(Var::from_dimacs(4)).cases().is_empty());
+}
+
+#[test]
+fn progress_callback() {
+    let qcnf = two_level_unsat();
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let mut progress = Vec::new();
+    let result = solver.solve_with_callback(|p| progress.push(*p));
+    assert_eq!(result, SolverResult::Unsatisfiable);
+    assert!(!progress.is_empty());
+    for (idx, p) in progress.iter().enumerate() {
+        assert_eq!(p.conflicts as usize, idx + 1);
+    }
+}
+
+#[test]
+fn unit_existential_after_reduction() {
+    let qcnf = qcnf_formula![
+        e 1 3;
+        a 2;
+        // reduces to the unit clause 1
+        1 2;
+        -1 3 -2;
+        1 3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn universal_clause_is_unsat() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        1;
+        -1 2;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+}
+
+#[test]
+fn reset() {
+    let unsat = qcnf_formula![
+        a 1;
+        e 2;
+        1 -2;
+        -1 2;
+        -1 -2;
+    ];
+    let mut solver = IncDet::from_qcnf(&unsat);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+
+    solver.reset();
+    assert_eq!(solver.prefix().count(), 0);
+    assert_eq!(solver.allocator.len(), 0);
+    assert_eq!(solver.stats.global.conflicts, 0);
+
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(1)]);
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(2)]);
+    solver.add_input_clause(&[Lit::from_dimacs(1), Lit::from_dimacs(-2)]);
+    solver.add_input_clause(&[Lit::from_dimacs(-1), Lit::from_dimacs(2)]);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn scope_sizes() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        // 2 is bound twice and moves to the existential scope
+        e 2 4;
+        1 2 3;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.scope_sizes(), vec![(QuantTy::Forall, 1), (QuantTy::Exists, 3)]);
+}
+
+#[test]
+fn declaration_order() {
+    let vars = |vars: &[i32]| vars.iter().map(|&v| Var::from_dimacs(v)).collect::<Vec<_>>();
+    let mut solver = IncDet::default();
+    solver._quantify(QuantTy::Forall, &vars(&[3, 1, 2]));
+    // merged into the same scope, 1 keeps its first position
+    solver._quantify(QuantTy::Forall, &vars(&[5, 1]));
+    // 2 moves to the existential scope
+    solver._quantify(QuantTy::Exists, &vars(&[6, 2, 4]));
+    solver._quantify(QuantTy::Forall, &vars(&[7]));
+    // 2 moves again, the remaining variables keep their order
+    solver._quantify(QuantTy::Exists, &vars(&[8, 2]));
+    let prefix: Vec<_> = solver.prefix().map(|(quant, vars)| (quant, vars.to_vec())).collect();
+    assert_eq!(
+        prefix,
+        [
+            (QuantTy::Forall, vars(&[3, 1, 5])),
+            (QuantTy::Exists, vars(&[6, 4])),
+            (QuantTy::Forall, vars(&[7])),
+            (QuantTy::Exists, vars(&[8, 2])),
+        ]
+    );
+
+    assert_eq!(solver.dependencies(Var::from_dimacs(6)).collect::<Vec<_>>(), vars(&[3, 1, 5]));
+    assert_eq!(solver.dependencies(Var::from_dimacs(2)).collect::<Vec<_>>(), vars(&[3, 1, 5, 7]));
+    assert_eq!(solver.dependencies(Var::from_dimacs(3)).count(), 0);
+    assert_eq!(solver.dependencies(Var::from_dimacs(20)).count(), 0);
+}
+
+#[test]
+fn recorded_reductions() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let mut solver = IncDet::builder().record_reductions(true).build();
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(1)]);
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(2)]);
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(3), Var::from_dimacs(4)]);
+    let reduced = solver.add_input_clause(&lits(&[1, 2, -3, 4])).unwrap();
+    assert_eq!(solver.clause(reduced), lits(&[1, 2]));
+    assert_eq!(solver.reduced_universals(reduced), Some(&lits(&[-3, 4])[..]));
+    let unreduced = solver.add_input_clause(&lits(&[-1, -2])).unwrap();
+    assert_eq!(solver.reduced_universals(unreduced), Some(&[][..]));
+    let empty = solver.add_input_clause(&lits(&[1, 3])).unwrap();
+    assert!(solver.clause(empty).is_empty());
+    assert_eq!(solver.reduced_universals(empty), Some(&lits(&[1, 3])[..]));
+
+    let mut solver = IncDet::default();
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(1)]);
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(2)]);
+    let handle = solver.add_input_clause(&lits(&[1, 2])).unwrap();
+    assert_eq!(solver.reduced_universals(handle), None);
+}
+
+#[test]
+fn clauses_containing() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let mut solver = IncDet::default();
+    solver._quantify(QuantTy::Forall, &[Var::from_dimacs(1)]);
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(2), Var::from_dimacs(3)]);
+    let first = solver.add_input_clause(&lits(&[1, 2])).unwrap();
+    let second = solver.add_input_clause(&lits(&[-2, 3])).unwrap();
+    let containing = |solver: &mut IncDet, var| {
+        solver.clauses_containing(Var::from_dimacs(var)).collect::<Vec<_>>()
+    };
+    assert_eq!(containing(&mut solver, 2), [first, second]);
+    assert_eq!(containing(&mut solver, 1), [first]);
+    // clauses added after building the occurrence lists are included
+    solver._quantify(QuantTy::Exists, &[Var::from_dimacs(4)]);
+    let third = solver.add_input_clause(&lits(&[-1, -3, 4])).unwrap();
+    assert_eq!(containing(&mut solver, 3), [second, third]);
+    assert_eq!(containing(&mut solver, 4), [third]);
+    assert!(containing(&mut solver, 5).is_empty());
+}
+
+#[test]
+fn disable_universal_reduction() {
+    let clause = [Lit::from_dimacs(1), Lit::from_dimacs(2)];
+    for (enabled, expected) in [(true, &clause[..1]), (false, &clause[..])] {
+        let mut solver = IncDet::default().with_universal_reduction(enabled);
+        solver._quantify(QuantTy::Exists, &[Var::from_dimacs(1)]);
+        solver._quantify(QuantTy::Forall, &[Var::from_dimacs(2)]);
+        let handle = solver.add_input_clause(&clause).unwrap();
+        assert_eq!(solver.clause(handle), expected);
+    }
+}
+
+#[test]
+fn to_qcnf() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        a 3;
+        1 2 3;
+        -1 -2;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    let expected = qcnf_formula![
+        a 1;
+        e 2;
+        a 3;
+        1 2;
+        -1 -2;
+    ];
+    assert_eq!(solver.to_qcnf(), expected);
+}
+
+#[test]
+fn universal_reduction_matrix() {
+    // (prefix, clauses, expected result, expected stored clauses)
+    let cases = [
+        // universal-only clause reduces to the empty clause
+        (qcnf_formula![a 1 2; e 3; 1 2; 3;], SolverResult::Unsatisfiable, vec![vec![], vec![3]]),
+        // empty clause
+        (qcnf_formula![a 1; e 2; ; 2;], SolverResult::Unsatisfiable, vec![vec![], vec![2]]),
+        // universal-only tautology is ignored
+        (qcnf_formula![a 1; e 2; 1 -1; 2;], SolverResult::Satisfiable, vec![vec![2]]),
+        // all existentials are outer, the universal is removed
+        (qcnf_formula![e 1; a 2; 1 2; -1 2;], SolverResult::Unsatisfiable, vec![vec![1], vec![-1]]),
+        (qcnf_formula![e 1; a 2; 1 2; 1 -2;], SolverResult::Satisfiable, vec![vec![1], vec![1]]),
+        // the universal is outer, the clause is kept
+        (
+            qcnf_formula![a 1; e 2; 1 2; -1 2;],
+            SolverResult::Satisfiable,
+            vec![vec![1, 2], vec![-1, 2]],
+        ),
+    ];
+    for (qcnf, expected, stored) in cases {
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        let clauses: Vec<Vec<i32>> = solver
+            .to_qcnf()
+            .matrix
+            .iter()
+            .map(|clause| clause.iter().map(|lit| lit.to_dimacs()).collect())
+            .collect();
+        assert_eq!(clauses, stored, "{qcnf}");
+        assert_eq!(solver.solve(), expected, "{qcnf}");
+    }
+}
+
+#[test]
+fn builder() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        1 -2;
+        -1 2;
+    ];
+    let mut solver =
+        IncDet::builder().universal_reduction(false).vsids_decay(0.8).build_from_qcnf(&qcnf);
+    assert!(solver.disable_universal_reduction);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn sat_trace() {
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        2;
+        2 -3;
+        -2 3;
+        2 3;
+    ];
+    let buffer = Buffer::default();
+    let mut solver = IncDet::builder().sat_trace(buffer.clone()).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), IncDet::from_qcnf(&qcnf).solve());
+
+    let trace = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = trace.lines().collect();
+    assert!(lines.iter().any(|line| line.starts_with("c solver ")));
+    let calls = lines.iter().filter(|line| line.starts_with("a ")).count();
+    let results = lines.iter().filter(|line| line.starts_with("c result ")).count();
+    assert!(calls > 0);
+    assert_eq!(calls, results);
+    for line in lines.iter().filter(|line| !line.starts_with('c')) {
+        assert!(line.ends_with('0'), "{line}");
+        let mut lits = line.trim_start_matches("a ").split_whitespace();
+        assert!(lits.all(|lit| lit.parse::<isize>().is_ok()), "{line}");
+    }
+}
+
+#[test]
+fn is_deterministic() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        1 -2;
+        -1 2;
+        2 3 1;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    // 2 is defined by the universal 1, 3 has to be decided
+    assert!(solver.is_deterministic(Var::from_dimacs(2)));
+    assert!(!solver.is_deterministic(Var::from_dimacs(3)));
+    // universals and unknown variables are never deterministic
+    assert!(!solver.is_deterministic(Var::from_dimacs(1)));
+    assert!(!solver.is_deterministic(Var::from_dimacs(10)));
+}
+
+#[test]
+fn root_implications() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        1 2 3;
+        -3;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    // one implication per clause, regardless of the number of universal literals
+    assert_eq!(solver.graph[Lit::from_dimacs(3)].len(), 1);
+    // constants have no reason in the implication graph
+    assert!(solver.graph[Lit::from_dimacs(-3)].is_empty());
+}
+
+#[test]
+fn assignment_level() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        1 -2;
+        -1 2;
+        2 3 1;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    // 2 is propagated at the root level, 3 is assigned by the only decision
+    assert_eq!(solver.assignment_level(Var::from_dimacs(2)), Some(0));
+    assert_eq!(solver.assignment_level(Var::from_dimacs(3)), Some(1));
+    assert_eq!(solver.assignment_level(Var::from_dimacs(1)), None);
+    assert_eq!(solver.assignment_level(Var::from_dimacs(10)), None);
+
+    let levels: Vec<(u32, Vec<Var>)> = solver
+        .trail_levels()
+        .map(|(lvl, lits)| (lvl, lits.iter().map(|lit| lit.var()).collect()))
+        .collect();
+    assert_eq!(levels, vec![(0, vec![Var::from_dimacs(2)]), (1, vec![Var::from_dimacs(3)])]);
+}
+
+#[test]
+fn max_learnt_size() {
+    let formulas = [
+        qcnf_formula![
+            a 1;
+            e 2 3;
+            2;
+            2 -3;
+            -2 3;
+            2 3;
+        ],
+        two_level_unsat(),
+    ];
+    let mut learnt = 0;
+    for qcnf in formulas {
+        let expected = IncDet::from_qcnf(&qcnf).solve();
+        let mut solver = IncDet::builder().max_learnt_size(0).build_from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), expected);
+        let stats = &solver.stats.global;
+        assert_eq!(stats.oversized_learnt_clauses, stats.added_clauses);
+        assert_eq!(solver.scheduled_for_deletion().len(), stats.added_clauses as usize);
+        learnt += stats.added_clauses;
+    }
+    assert!(learnt > 0);
+
+    // the solve loop removes the scheduled clauses once enough of them are learnt
+    let qcnf = qcnf_formula![
+        a 1 2 3 4;
+        e 5 6 7 8 9 10 11 12 13;
+        9 5 11;
+        -4 -7 -10;
+        -13 8;
+        -10 12;
+        5 7;
+        9 11 7;
+        -8 -7;
+        -11 -12;
+        4 12;
+        -5 10;
+        13 12;
+        -9 -12;
+        -9 -5;
+        -11 8;
+    ];
+    let expected = crate::expand::solve_by_expansion(&qcnf);
+    let mut solver = IncDet::builder().max_learnt_size(0).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    let stats = &solver.stats.global;
+    assert!(stats.learnt_compactions > 0);
+    assert!(solver.scheduled_for_deletion().len() < stats.oversized_learnt_clauses as usize);
+    assert!(solver.allocator.len() < qcnf.matrix.len() + stats.added_clauses as usize);
+}
+
+#[test]
+fn count_models() {
+    // 2 is equivalent to 1
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        1 -2;
+        -1 2;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), Some(1));
+    // 3 <=> 1 & 2, 4 is unconstrained in all four universal assignments
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4;
+        1 -3; 2 -3; -1 -2 3;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), Some(16));
+    // 3 is only constrained if 1 is false
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        1 3;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), Some(4 * 2));
+    // without universals, the models are counted
+    let qcnf = qcnf_formula![
+        e 1 2;
+        1 2;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), Some(3));
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2;
+        1 -2;
+        -1 2;
+        -1 -2;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), Some(0));
+    // unsupported prefix
+    let qcnf = qcnf_formula![
+        e 1;
+        a 2;
+        e 3;
+        1 2 3;
+    ];
+    assert_eq!(IncDet::count_models(&qcnf), None);
+}
+
+#[test]
+fn checkpoint() -> std::io::Result<()> {
+    let qcnf = two_level_unsat();
+    let expected = IncDet::from_qcnf(&qcnf).solve();
+
+    // interrupt the solver at the first conflict
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.set_interrupt(interrupt.clone());
+    let result = solver.solve_with_callback(|_| interrupt.store(true, Ordering::Relaxed));
+    assert_eq!(result, SolverResult::Unknown);
+
+    let mut checkpoint = Vec::new();
+    solver.save_state(&mut checkpoint)?;
+    let mut restored = IncDet::load_state(checkpoint.as_slice())?;
+    assert_eq!(restored.stats.global.conflicts, solver.stats.global.conflicts);
+    assert_eq!(restored.to_qcnf(), solver.to_qcnf());
+
+    // saving the restored solver yields the same checkpoint
+    let mut second = Vec::new();
+    restored.save_state(&mut second)?;
+    assert_eq!(second, checkpoint);
+
+    assert_eq!(restored.solve(), expected);
+
+    checkpoint[0] = b'X';
+    let err = IncDet::load_state(checkpoint.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+}
+
+#[test]
+fn checkpoint_configuration() -> std::io::Result<()> {
+    use crate::qdimacs::QdimacsParser;
+
+    let mut solver = IncDet::builder()
+        .record_reductions(true)
+        .vsids_decay(0.8)
+        .max_learnt_size(3)
+        .conflict_strategy(ConflictStrategy::AlwaysLocal)
+        .global_check_limit(2)
+        .vsids_seeding(true)
+        .literal_order(LiteralOrder::ExistentialFirst)
+        .memory_limit(1 << 20)
+        .progress_interval(Duration::from_millis(1500))
+        .clause_minimization(false)
+        .clause_strengthening(false)
+        .build();
+    let input = "p cnf 4 3\na 1 0\ne 2 0\na 3 4 0\n1 2 -3 4 0\n-1 -2 0\n-2 3\n 4 0\n";
+    QdimacsParser::new(std::io::Cursor::new(input))
+        .source_lines(true)
+        .parse_into(&mut solver)
+        .unwrap();
+    assert_eq!(solver.source_lines.len(), 3);
+    assert_eq!(solver.reduced_universals.as_ref().map(HashMap::len), Some(2));
+
+    let mut checkpoint = Vec::new();
+    solver.save_state(&mut checkpoint)?;
+    let restored = IncDet::load_state(checkpoint.as_slice())?;
+    assert_eq!(restored.to_qcnf(), solver.to_qcnf());
+    assert_eq!(restored.source_lines, solver.source_lines);
+    assert_eq!(restored.reduced_universals, solver.reduced_universals);
+    assert_eq!(restored.disable_universal_reduction, solver.disable_universal_reduction);
+    assert!((restored.vsids.decay_factor() - 0.8).abs() < f64::EPSILON);
+    assert_eq!(restored.max_learnt_size, Some(3));
+    assert_eq!(restored.conflict_strategy, ConflictStrategy::AlwaysLocal);
+    assert_eq!(restored.global_check_limit, Some(2));
+    assert!(restored.seed_vsids);
+    assert_eq!(restored.literal_order, LiteralOrder::ExistentialFirst);
+    assert_eq!(restored.memory_limit, Some(1 << 20));
+    assert_eq!(restored.progress_interval, Some(Duration::from_millis(1500)));
+    assert!(restored.disable_clause_minimization);
+    assert!(restored.disable_clause_strengthening);
+
+    let mut second = Vec::new();
+    restored.save_state(&mut second)?;
+    assert_eq!(second, checkpoint);
+    Ok(())
+}
+
+#[test]
+fn only_universals() {
+    let cases = [
+        // tautologies are dropped, the matrix is empty
+        (qcnf_formula![a 1; 1 -1;], SolverResult::Satisfiable),
+        (qcnf_formula![a 1 2; 1 -1 2;], SolverResult::Satisfiable),
+        // every non-tautological clause is falsified by the universal player
+        (qcnf_formula![a 1; 1;], SolverResult::Unsatisfiable),
+        (qcnf_formula![a 1 2; 1 -1; 1 2;], SolverResult::Unsatisfiable),
+    ];
+    for (qcnf, expected) in cases {
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), expected, "{qcnf}");
+        assert_eq!(crate::expand::solve_by_expansion(&qcnf), expected, "{qcnf}");
+    }
+}
+
+#[test]
+fn vsids_seeding() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3 4;
+        // 4 occurs in every clause, but is not the first variable in the heap
+        1 2 4;
+        -1 3 4;
+        2 3 -4;
+        -2 -3 -4;
+    ];
+    for (seeding, expected) in [(false, 2), (true, 4)] {
+        let mut solver = IncDet::builder().vsids_seeding(seeding).build_from_qcnf(&qcnf);
+        solver.build_watchlist();
+        solver.build_vsids_heap();
+        assert_eq!(solver.next_decision_variable(), Some(Var::from_dimacs(expected)));
+    }
+}
+
+#[test]
+fn counterexamples() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    // refuted by every universal assignment except 1 & 2
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        1 -3;
+        2 -3;
+        3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+    let mut counterexamples: Vec<_> = solver.counterexamples().collect();
+    counterexamples.sort();
+    let mut expected = vec![lits(&[-1, -2]), lits(&[-1, 2]), lits(&[1, -2])];
+    expected.sort();
+    assert_eq!(counterexamples, expected);
+    assert_eq!(solver.counterexamples().take(2).count(), 2);
+
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3;
+        1 -3;
+        2 -3;
+        -1 -2 3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    assert_eq!(solver.counterexamples().next(), None);
+}
+
+#[test]
+fn source_lines() -> Result<(), crate::qdimacs::ParseError> {
+    use crate::qdimacs::QdimacsParser;
+
+    let input = "c comment\np cnf 3 3\na 1 0\ne 2 3 0\n1 2 0\n\n-1 3\n  -2 0\n2 3 0\n";
+    for relaxed in [false, true] {
+        let mut solver: IncDet = QdimacsParser::new(std::io::Cursor::new(input))
+            .source_lines(true)
+            .relaxed_prefix(relaxed)
+            .parse()?;
+        let lines: Vec<_> = solver
+            .clauses_containing(Var::from_dimacs(2))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| solver.source_line(handle))
+            .collect();
+        assert_eq!(lines, [Some(5), Some(7), Some(9)]);
+    }
+
+    let solver: IncDet = QdimacsParser::new(std::io::Cursor::new(input)).parse()?;
+    assert!(solver.source_lines.is_empty());
+    Ok(())
+}
+
+#[test]
+fn trivial_unique_consequence() {
+    let cases = [
+        // no implications
+        (qcnf_formula![a 1; e 2 3; 2 3;], Some(false)),
+        // a single implication
+        (qcnf_formula![a 1; e 2; 1 2;], Some(false)),
+        // a unit implication
+        (qcnf_formula![a 1; e 2; 2; 1 -2;], Some(true)),
+        // complementary binary implications
+        (qcnf_formula![a 1; e 2; 1 2; -1 -2;], Some(true)),
+        // binary implications that are not complementary
+        (qcnf_formula![a 1 3; e 2; 1 2; -3 -2;], Some(false)),
+        (qcnf_formula![a 1 3; e 2; 1 2; -1 3 -2;], Some(false)),
+        // the general case requires a SAT call
+        (qcnf_formula![a 1 3; e 2; 1 2; 3 2; -1 -3 -2;], None),
+    ];
+    for (qcnf, expected) in cases {
+        let var = Var::from_dimacs(2);
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.trivial_unique_consequence(var), expected, "{qcnf}");
+        let mut sat_solver = varisat::Solver::new();
+        for clause in &qcnf.matrix {
+            let clause: Vec<_> = clause
+                .iter()
+                .filter(|lit| lit.var() != var)
+                .map(|&lit| varisat::Lit::from(lit))
+                .collect();
+            varisat::ExtendFormula::add_clause(&mut sat_solver, &clause);
+        }
+        let unique = !sat_solver.solve().unwrap();
+        assert_eq!(solver.has_unique_consequence(var).unwrap(), unique, "{qcnf}");
+        assert_eq!(expected.unwrap_or(unique), unique, "{qcnf}");
+    }
+}
+
+#[test]
+fn unit_clause() {
+    use crate::incdet::propagation::assignment::Value;
+
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        -2;
+        1 2 3;
+        -1 2 -3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.build_watchlist();
+    assert!(matches!(solver.propagate(), Ok(None)));
+    assert_eq!(solver.assignment[Var::from_dimacs(2)], Some(Value::False));
+    assert_eq!(solver.stats.skolem.constant_propagations, 1);
+    assert_eq!(IncDet::from_qcnf(&qcnf).solve(), SolverResult::Satisfiable);
+}
+
+#[test]
+fn literal_order() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let cases = [
+        (LiteralOrder::AsGiven, lits(&[4, -1, 3])),
+        (LiteralOrder::Sorted, lits(&[-1, 3, 4])),
+        (LiteralOrder::ExistentialFirst, lits(&[3, 4, -1])),
+    ];
+    for (order, expected) in cases {
+        let mut solver = IncDet::builder().literal_order(order).build();
+        solver._quantify(QuantTy::Forall, &[Var::from_dimacs(1), Var::from_dimacs(2)]);
+        solver._quantify(QuantTy::Exists, &[Var::from_dimacs(3), Var::from_dimacs(4)]);
+        let handle = solver.add_input_clause(&lits(&[4, -1, 4, 3])).unwrap();
+        assert_eq!(solver.clause(handle), expected, "{order:?}");
+    }
+
+    // the order affects propagation, but not the result
+    let formulas = [
+        two_level_unsat(),
+        qcnf_formula![
+            a 2 1;
+            e 5 4 3;
+            4 -1 3;
+            -4 2 5;
+            -5 3 -2;
+            1 -3 -4 5;
+        ],
+    ];
+    for qcnf in formulas {
+        let expected = crate::expand::solve_by_expansion(&qcnf);
+        for order in [LiteralOrder::AsGiven, LiteralOrder::Sorted, LiteralOrder::ExistentialFirst] {
+            let mut solver = IncDet::builder().literal_order(order).build_from_qcnf(&qcnf);
+            assert_eq!(solver.solve(), expected, "{order:?} {qcnf}");
+        }
+    }
+}
+
+#[test]
+fn single_scope() {
+    let cases = [
+        // pure SAT instances
+        (qcnf_formula![e 1 2 3; 1 2; -1 3; -2 -3; -3 2;], SolverResult::Satisfiable),
+        (qcnf_formula![e 1 2; 1 2; -1 2; 1 -2; -1 -2;], SolverResult::Unsatisfiable),
+        // only universal variables
+        (qcnf_formula![a 1 2; 1 2;], SolverResult::Unsatisfiable),
+    ];
+    for (qcnf, expected) in cases {
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), expected, "{qcnf}");
+        assert_eq!(crate::expand::solve_by_expansion(&qcnf), expected, "{qcnf}");
+    }
+
+    // empty prefix
+    let mut solver = IncDet::from_qcnf(&crate::qcnf::QCNF::default());
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    let mut solver = IncDet::default();
+    solver.add_input_clause(&[]);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+}
+
+#[test]
+fn incremental_vsids() {
+    let qcnf = qcnf_formula![
+        a 1 2 3;
+        e 4 5 6 7 8 9 10 11 12 13;
+        -2 5 -13; 2 -9 11; 2 3 4; 2 -9 -10; 9 -10 -13; 4 5 -9; -5 -9 10; 3 -4 -6;
+        4 5 13; -4 -10 -11; 4 -12 -13; 4 8 13; -3 -8 10; 2 9 13; -1 -4 -12; -2 -4 -5;
+        -1 -10 -13; -3 5 7; 2 7 -13; 1 -10 11; -8 9 11; -4 -8 -13; -1 -2 7; -1 -9 12;
+        9 -12 13; -3 -6 -7; 4 -7 -9; -1 4 6; 3 5 12;
+    ];
+    let last = [Lit::from_dimacs(-2), Lit::from_dimacs(3), Lit::from_dimacs(8)];
+
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    let activities: Vec<_> =
+        (4..=13).map(|var| solver.vsids.activity(Var::from_dimacs(var))).collect();
+    let decisions = solver.stats.global.decisions;
+    solver.add_input_clause(&last);
+    // the activities are kept for the next solve
+    let kept: Vec<_> = (4..=13).map(|var| solver.vsids.activity(Var::from_dimacs(var))).collect();
+    assert_eq!(kept, activities);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    let incremental = solver.stats.global.decisions - decisions;
+
+    let mut extended = qcnf;
+    extended.matrix.push(last.to_vec());
+    let mut fresh = IncDet::from_qcnf(&extended);
+    assert_eq!(fresh.solve(), SolverResult::Satisfiable);
+    assert!(incremental < fresh.stats.global.decisions);
+}
+
+#[test]
+fn conflict_display() {
+    let mut assignment = crate::datastructure::LitSet::default();
+    assignment.extend([-4, 1, -2].map(Lit::from_dimacs));
+    let conflict = super::Conflict { var: Var::from_dimacs(3), assignment };
+    assert_eq!(conflict.to_string(), "conflict on var 3: {1 -2 -4}");
+}
+
+#[test]
+fn clause_minimization() {
+    let formulas = [
+        qcnf_formula![
+            a 1;
+            e 2 3;
+            2;
+            2 -3;
+            -2 3;
+            2 3;
+        ],
+        two_level_unsat(),
+    ];
+    for qcnf in formulas {
+        let expected = IncDet::from_qcnf(&qcnf).solve();
+        let mut solver = IncDet::builder().clause_minimization(false).build_from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), expected);
+        assert_eq!(solver.stats.global.minimized_literals, 0);
+    }
+
+    // redundant literals are removed with the default settings
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4 5;
+        -3 4;
+        -5;
+        5 -4;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let expected = solver.solve();
+    assert!(solver.stats.global.minimized_literals > 0);
+    let mut solver = IncDet::builder().clause_minimization(false).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    assert_eq!(solver.stats.global.minimized_literals, 0);
+
+    // strengthening does not depend on minimization
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3 4 5;
+        4 -5 2;
+        4 -2;
+        -4 -5;
+        5 1;
+    ];
+    let expected = IncDet::from_qcnf(&qcnf).solve();
+    let mut solver = IncDet::builder().clause_minimization(false).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    assert!(solver.stats.global.strengthened_literals > 0);
+}
+
+#[test]
+fn clause_strengthening() {
+    let qcnf = two_level_unsat();
+    let expected = IncDet::from_qcnf(&qcnf).solve();
+    let mut solver = IncDet::builder().clause_strengthening(false).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    assert_eq!(solver.stats.global.strengthened_literals, 0);
+}
+
+#[test]
+fn check_learnt_clause() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        -1 2;
+        -2 3;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    // resolvent of the two clauses
+    solver.check_learnt_clause(&[Lit::from_dimacs(-1), Lit::from_dimacs(3)]);
+}
+
+#[test]
+#[should_panic(expected = "is not entailed")]
+fn check_learnt_clause_not_entailed() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        -1 2;
+        -2 3;
+    ];
+    let solver = IncDet::from_qcnf(&qcnf);
+    solver.check_learnt_clause(&[Lit::from_dimacs(3)]);
+}
+
+#[test]
+fn try_solve() {
+    let qcnf = two_level_unsat();
+    let expected = IncDet::from_qcnf(&qcnf).solve();
+    assert_eq!(IncDet::from_qcnf(&qcnf).try_solve().unwrap(), expected);
+
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.set_interrupt(Arc::new(AtomicBool::new(true)));
+    assert!(matches!(solver.try_solve(), Err(SolveError::Timeout)));
+    // the infallible variant keeps reporting an unknown result
+    assert_eq!(solver.solve(), SolverResult::Unknown);
+
+    let qcnf = qcnf_formula![
+        e 1;
+        a 2;
+        e 3;
+        1 2 3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert!(matches!(solver.try_solve(), Err(SolveError::UnsupportedPrefix { scopes: 3 })));
+    assert_eq!(solver.solve(), SolverResult::Unknown);
+}
+
+#[test]
+fn compact() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3 4;
+        2;
+        -1 3 4;
+        1 -3;
+        -3 -4;
+    ];
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    // satisfied by the unit clause, duplicate, and subsumed clauses
+    let redundant = [lits(&[2, 3]), lits(&[-1, 3, 4]), lits(&[-1, -2, 3, 4]), lits(&[1, -3, 4])];
+
+    // exactly the redundant clauses are removed
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    for clause in &redundant {
+        solver.add_input_clause(clause);
+    }
+    let kept = lits(&[3, 1]);
+    solver.add_input_clause(&kept);
+    let mut expected_qcnf = qcnf.clone();
+    expected_qcnf.matrix.push(kept);
+    assert_eq!(solver.compact(), expected_qcnf.matrix.len());
+    assert!(solver.to_qcnf().semantically_eq(&expected_qcnf), "{}", solver.to_qcnf());
+
+    let expected = IncDet::from_qcnf(&qcnf).solve();
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    for clause in &redundant {
+        solver.add_input_clause(clause);
+    }
+    let learnt = solver.stats.global.added_clauses as usize;
+    assert_eq!(solver.allocator.len(), qcnf.matrix.len() + learnt + redundant.len());
+    let remaining = solver.compact();
+    assert!(remaining <= qcnf.matrix.len() + learnt);
+    assert_eq!(remaining, solver.allocator.len());
+    assert!(solver.to_qcnf().matrix.iter().all(|clause| !clause.contains(&Lit::from_dimacs(-2))));
+    assert_eq!(solver.solve(), expected);
+
+    // learnt clauses exceeding the size limit are removed
+    let mut solver = IncDet::builder().max_learnt_size(0).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), expected);
+    assert!(solver.compact() <= qcnf.matrix.len());
+    assert!(solver.scheduled_for_deletion().is_empty());
+    assert_eq!(solver.solve(), expected);
+}
+
+#[test]
+fn redundant_learnt_clause() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3;
+        1 2 3;
+        -1 -2 -3;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.build_watchlist();
+    // equal up to the order and duplicates of literals
+    let learnt = lits(&[3, 1, 2, 3]);
+    assert!(solver.find_clause(&learnt).is_some());
+    assert!(solver.find_clause(&lits(&[1, 2])).is_none());
+    // both existential literals are unassigned, the stored clause is watched
+    assert_eq!(solver.redundant_implication(&learnt), None);
+
+    solver.assign_and_propagate(Lit::from_dimacs(-2), true, false);
+    assert_eq!(solver.redundant_implication(&learnt), Some(Lit::from_dimacs(3)));
+    let num_clauses = solver.allocator.len();
+    solver.add_learnt_clause(&learnt);
+    assert_eq!(solver.allocator.len(), num_clauses);
+    assert_eq!(solver.stats.global.redundant_learnt_clauses, 1);
+    assert_eq!(solver.stats.global.added_clauses, 0);
+    assert!(solver.propagation.contained(Var::from_dimacs(3)));
+
+    // a learnt clause that is not stored yet is added
+    solver.add_learnt_clause(&lits(&[1, -2, 3]));
+    assert_eq!(solver.allocator.len(), num_clauses + 1);
+    assert_eq!(solver.stats.global.added_clauses, 1);
+}
+
+#[test]
+fn add_scope() {
+    let lits = |clause: &[i32]| clause.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+    let mut solver = IncDet::default();
+    let forall = solver.add_scope(QuantTy::Forall);
+    // adjacent scopes with the same quantifier are merged
+    assert_eq!(solver.add_scope(QuantTy::Forall), forall);
+    let exists = solver.add_scope(QuantTy::Exists);
+    assert_ne!(forall, exists);
+    solver.bind(exists, Var::from_dimacs(3));
+    solver.bind(forall, Var::from_dimacs(1));
+    solver.bind(exists, Var::from_dimacs(2));
+    // binding again moves the variable
+    solver.bind(forall, Var::from_dimacs(2));
+    solver.bind(exists, Var::from_dimacs(2));
+    let prefix: Vec<_> = solver.prefix().map(|(quant, vars)| (quant, vars.to_vec())).collect();
+    assert_eq!(
+        prefix,
+        [
+            (QuantTy::Forall, vec![Var::from_dimacs(1)]),
+            (QuantTy::Exists, vec![Var::from_dimacs(3), Var::from_dimacs(2)])
+        ]
+    );
+
+    solver.add_input_clause(&lits(&[1, 2]));
+    solver.add_input_clause(&lits(&[-2, 3]));
+    solver.add_input_clause(&lits(&[-3]));
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+}
+
+#[test]
+fn cached_lit_count() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4 5;
+        2 -3;
+        -1 -2 3;
+        1 -4;
+        -3 -4;
+        1 3 4;
+        -1 5;
+        1 -5;
+        3 4 5;
+    ];
+    let assert_cached = |solver: &IncDet| {
+        for (lit, implications) in solver.skolem.iter() {
+            let count: usize =
+                implications.implications().map(|c| solver.allocator[c].lits().len()).sum();
+            assert_eq!(implications.lit_count(), count, "{lit}");
+        }
+    };
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.build_watchlist();
+    assert!(matches!(solver.propagate(), Ok(None)));
+    assert_cached(&solver);
+    solver.assign_and_propagate(Lit::from_dimacs(-3), true, false);
+    assert_cached(&solver);
+    solver.assign_and_propagate(Lit::from_dimacs(4), true, false);
+    assert_cached(&solver);
+    solver.backtrack_to(DecLvl::ROOT.successor());
+    assert_cached(&solver);
+    solver.backtrack_to(DecLvl::ROOT);
+    assert_cached(&solver);
+
+    assert_eq!(solver.solve(), IncDet::from_qcnf(&qcnf).solve());
+    assert_cached(&solver);
+}
+
+#[test]
+fn unsat_core() {
+    let solve = |qcnf: &crate::qcnf::QCNF| {
+        let mut solver = IncDet::default();
+        for (quant, vars) in &qcnf.prefix {
+            solver._quantify(*quant, vars);
+        }
+        let handles: Vec<_> =
+            qcnf.matrix.iter().map(|clause| solver.add_input_clause(clause).unwrap()).collect();
+        (solver.solve(), solver.unsat_core(), handles)
+    };
+
+    // the implication of 3 is not needed to refute the formula
+    let (result, core, handles) = solve(&qcnf_formula![
+        a 1;
+        e 2 3 4;
+        1 2;
+        -1 3;
+        1 -2;
+        3 4;
+    ]);
+    assert_eq!(result, SolverResult::Unsatisfiable);
+    assert_eq!(core, [handles[0], handles[2]]);
+
+    // the first clause reduces to the empty clause
+    let (result, core, handles) = solve(&qcnf_formula![
+        a 1;
+        e 2;
+        1;
+        2;
+    ]);
+    assert_eq!(result, SolverResult::Unsatisfiable);
+    assert_eq!(core, [handles[0]]);
+
+    // solved by the SAT backend
+    let (result, core, handles) = solve(&qcnf_formula![
+        e 1 2 3;
+        1;
+        3;
+        -1 2;
+        -2;
+    ]);
+    assert_eq!(result, SolverResult::Unsatisfiable);
+    assert_eq!(core, [handles[0], handles[2], handles[3]]);
+
+    let (result, core, _) = solve(&qcnf_formula![
+        a 1;
+        e 2;
+        1 2;
+    ]);
+    assert_eq!(result, SolverResult::Satisfiable);
+    assert!(core.is_empty());
+}
+
+#[test]
+fn progress_interval() {
+    let progress = |conflicts, decisions, decision_level| SolverProgress {
+        decisions,
+        conflicts,
+        added_clauses: conflicts,
+        decision_level,
+        assigned: 0,
+    };
+    let mut heartbeat = Heartbeat::new(Duration::ZERO, &progress(100, 20, 3));
+    assert!(heartbeat.is_due());
+    let line = heartbeat.report(&progress(12000, 340, 17));
+    assert!(line.starts_with("c 12000 conflicts, "), "{line}");
+    assert!(line.ends_with(" decisions/s, level 17"), "{line}");
+    assert!(!Heartbeat::new(Duration::from_secs(3600), &progress(0, 0, 0)).is_due());
+
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4;
+        1 3 4;
+        -1 -3 4;
+        2 -4;
+        -2 3 -4;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.progress_interval, None);
+    let expected = solver.solve();
+    let mut solver = IncDet::from_qcnf(&qcnf).with_progress_interval(Duration::ZERO);
+    assert_eq!(solver.progress_interval, Some(Duration::ZERO));
+    assert_eq!(solver.solve(), expected);
+    solver.reset();
+    assert_eq!(solver.progress_interval, Some(Duration::ZERO));
+}
+
+#[test]
+fn binary_watches() {
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3 4;
+        1 2 -3;
+        -1 -2 3;
+        2 3 4;
+        -2 -4;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    solver.build_watchlist();
+    let stats = solver.watch_stats();
+    assert_eq!(stats.binary_watches, 6);
+    assert_eq!(stats.watches, 2);
+
+    assert_eq!(solver.solve(), crate::expand::solve_by_expansion(&qcnf));
+    assert!(solver.stats.skolem.binary_implications > 0);
+}
+
+#[test]
+fn propagation_priority() {
+    let qcnf = qcnf_formula![
+        a 1 2;
+        e 3 4 5;
+        2 -3;
+        -1 -2 3;
+        1 -4;
+        -3 -4;
+        1 3 4;
+        -1 5;
+        1 -5;
+    ];
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    let var = Var::from_dimacs(5);
+    let priority = IncDet::propagation_priority(&solver.skolem, &solver.futile_checks, var);
+    assert_eq!(priority, 2);
+    solver.futile_checks[var] = 1;
+    assert_eq!(IncDet::propagation_priority(&solver.skolem, &solver.futile_checks, var), 1);
+    solver.futile_checks[var] = 100;
+    assert_eq!(IncDet::propagation_priority(&solver.skolem, &solver.futile_checks, var), 0);
+
+    // every global check either finds a conflict or increments the penalty
+    let mut solver = IncDet::from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Unsatisfiable);
+    let stats = &solver.stats;
+    let futile: u32 = solver.vars.iter().map(|(var, _)| solver.futile_checks[var]).sum();
+    assert!(futile + stats.global.conflicts >= stats.skolem.global_conflict_checks);
+}
+
+#[test]
+fn global_check_limit() {
+    // 2, 3, and 4 are propagated one after another, each with a global check
+    let qcnf = qcnf_formula![
+        a 1;
+        e 2 3 4;
+        -1 2;
+        1 -2;
+        -2 3;
+        2 -3;
+        -3 4;
+        3 -4;
+    ];
+    let build = |limit: Option<usize>| {
+        let builder = IncDet::builder().conflict_strategy(ConflictStrategy::Global);
+        let mut solver = match limit {
+            Some(limit) => builder.global_check_limit(limit),
+            None => builder,
+        }
+        .build_from_qcnf(&qcnf);
+        solver.build_watchlist();
+        solver
+    };
+
+    let mut solver = build(None);
+    assert!(matches!(solver.propagate(), Ok(None)));
+    assert_eq!(solver.stats.skolem.global_conflict_checks, 3);
+    assert_eq!(solver.stats.skolem.deferred_propagations, 0);
+
+    // the root level stops after one check, the next decision level checks one more
+    let mut solver = build(Some(1));
+    assert!(matches!(solver.propagate(), Ok(None)));
+    assert_eq!(solver.stats.skolem.global_conflict_checks, 1);
+    assert_eq!(solver.stats.skolem.deferred_propagations, 1);
+    assert!(solver.assignment.is_assigned(Var::from_dimacs(2)));
+    assert!(!solver.assignment.is_assigned(Var::from_dimacs(3)));
+    assert!(solver.propagation.contained(Var::from_dimacs(3)));
+    assert_eq!(solver.futile_checks[Var::from_dimacs(2)], 1);
+
+    solver.assign_and_propagate(Lit::from_dimacs(3), true, false);
+    assert!(matches!(solver.propagate(), Ok(None)));
+    assert_eq!(solver.stats.skolem.global_conflict_checks, 2);
+    assert!(solver.assignment.is_assigned(Var::from_dimacs(4)));
+
+    assert_eq!(build(Some(1)).solve(), SolverResult::Satisfiable);
+    let mut solver = IncDet::builder().global_check_limit(0).build_from_qcnf(&qcnf);
+    assert_eq!(solver.solve(), SolverResult::Satisfiable);
+    assert!(solver.stats.skolem.deferred_propagations > 0);
+}
@@ -44,6 +44,35 @@ impl Vsids {
         }
     }
 
+    pub(crate) fn decay_factor(&self) -> f64 {
+        *self.decay
+    }
+
+    /// Returns the activity score of the provided variable.
+    pub(crate) fn activity(&self, var: Var) -> f64 {
+        *self.heap.get_value(var)
+    }
+
+    /// Overwrites the activity score of the provided variable.
+    pub(crate) fn set_activity(&mut self, var: Var, activity: f64) {
+        self.heap.update_value(var, |_| NotNan::new(activity).expect("activity is a number"));
+    }
+
+    /// Returns the value currently used for bumping.
+    pub(crate) fn bump_value(&self) -> f64 {
+        *self.bump
+    }
+
+    pub(crate) fn set_bump_value(&mut self, bump: f64) {
+        self.bump = NotNan::new(bump).expect("bump value is a number");
+    }
+
+    /// Sets the decay factor, which has to be in the interval `(0, 1]`.
+    pub(crate) fn set_decay(&mut self, decay: f64) {
+        debug_assert!(decay > 0.0 && decay <= 1.0);
+        self.decay = NotNan::new(decay).unwrap();
+    }
+
     /// Decay all variable activities.
     pub(crate) fn decay(&mut self) {
         self.bump /= self.decay;
@@ -78,7 +107,7 @@ mod test {
     fn heap() {
         let mut vsids = Vsids::default();
         vsids.set_var_count(4);
-        let vars: Vec<_> = (0..4).map(Var::from_index).collect();
+        let vars: Vec<_> = Var::range(4).collect();
         for &var in &vars {
             vsids.add(var);
         }
@@ -96,11 +125,25 @@ mod test {
         assert_eq!(vsids.peek(), Some(vars[1]));
     }
 
+    #[test]
+    fn equal_activity() {
+        let mut vsids = Vsids::default();
+        vsids.set_var_count(4);
+        let vars: Vec<_> = Var::range(4).collect();
+        for &var in vars.iter().rev() {
+            vsids.add(var);
+            vsids.bump(var);
+        }
+        assert_eq!(vsids.peek(), Some(vars[0]));
+        vsids.remove(vars[0]);
+        assert_eq!(vsids.peek(), Some(vars[1]));
+    }
+
     #[test]
     fn decay() {
         let mut vsids = Vsids::default();
         vsids.set_var_count(4);
-        let vars: Vec<_> = (0..4).map(Var::from_index).collect();
+        let vars: Vec<_> = Var::range(4).collect();
         for &var in &vars {
             vsids.add(var);
         }
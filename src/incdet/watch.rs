@@ -1,4 +1,10 @@
-use crate::{clause::alloc::ClauseId, datastructure::LitVec, literal::Lit};
+use super::propagation::assignment::Assignment;
+use super::stats::WatchStats;
+use crate::{
+    clause::alloc::ClauseId,
+    datastructure::LitVec,
+    literal::{Lit, Var},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Watch {
@@ -6,26 +12,80 @@ pub struct Watch {
     pub(crate) clause: ClauseId,
 }
 
+/// The watch of a clause with exactly two existential literals.
+///
+/// Both existential literals are watched permanently, thus, the other literal is stored with the watch
+/// and propagation does not need to access the clause.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BinaryWatch {
+    /// A reference to a clause where the watched literal is contained.
+    pub(crate) clause: ClauseId,
+    /// The other existential literal of the clause.
+    pub(crate) other: Lit,
+    /// The number of literals of the clause, including universal literals.
+    pub(crate) len: usize,
+}
+
+/// The watched literals of the clauses.
+///
+/// The watch list starts disabled and is enabled once the initial watches are built, see [`WatchList::set_enabled`].
+/// Watches may only be added while the watch list is enabled, [`WatchList::add_watch`] ignores them otherwise.
+/// [`WatchList::clear`] removes all watches and disables the watch list again.
+/// Clauses with exactly two existential literals are watched by [`BinaryWatch`]es, which are kept separately.
 #[derive(Debug, Clone, Default)]
 pub(crate) struct WatchList {
     enabled: bool,
     watches: LitVec<Vec<Watch>>,
+    binary: LitVec<Vec<BinaryWatch>>,
 }
 
 impl WatchList {
     pub(crate) fn set_var_count(&mut self, count: usize) {
         self.watches.set_var_count(count);
+        self.binary.set_var_count(count);
     }
 
     pub(crate) fn clear(&mut self) {
         self.enabled = false;
         self.watches.clear();
+        self.binary.clear();
     }
 
+    /// Adds a watch for `lit`, does nothing if the watch list is disabled.
     pub(super) fn add_watch(&mut self, lit: Lit, watch: Watch) {
+        if !self.enabled {
+            return;
+        }
         self.watches[lit].push(watch);
     }
 
+    /// Adds a binary watch for `lit`, does nothing if the watch list is disabled.
+    pub(super) fn add_binary_watch(&mut self, lit: Lit, watch: BinaryWatch) {
+        if !self.enabled {
+            return;
+        }
+        self.binary[lit].push(watch);
+    }
+
+    /// Returns the binary watches of `lit`.
+    pub(crate) fn binary(&self, lit: Lit) -> &[BinaryWatch] {
+        &self.binary[lit]
+    }
+
+    /// Returns the binary watches of both literals of `var`, together with the watched literal.
+    pub(crate) fn iter_containing(&self, var: Var) -> impl Iterator<Item = (Lit, &BinaryWatch)> {
+        var.both_literals()
+            .into_iter()
+            .flat_map(move |lit| self.binary[lit].iter().map(move |watch| (lit, watch)))
+    }
+
+    /// Returns whether `lit` has an implication from a binary clause, i.e., `lit` is unassigned and
+    /// the other existential literal of one of its binary clauses is assigned.
+    pub(crate) fn is_implied_true(&self, lit: Lit, assignment: &Assignment) -> bool {
+        !assignment.is_assigned(lit.var())
+            && self.binary[lit].iter().any(|watch| assignment.is_assigned(watch.other.var()))
+    }
+
     pub(crate) fn enabled(&self) -> bool {
         self.enabled
     }
@@ -33,6 +93,34 @@ impl WatchList {
     pub(crate) fn set_enabled(&mut self) {
         self.enabled = true;
     }
+
+    /// Returns the approximate number of bytes used by the watches.
+    pub(crate) fn memory_usage(&self) -> usize {
+        let watches: usize = self.watches.iter().map(|(_, watches)| watches.capacity()).sum();
+        let binary: usize = self.binary.iter().map(|(_, watches)| watches.capacity()).sum();
+        watches * std::mem::size_of::<Watch>() + binary * std::mem::size_of::<BinaryWatch>()
+    }
+
+    pub(crate) fn stats(&self) -> WatchStats {
+        let mut stats = WatchStats::default();
+        let mut lits = 0_usize;
+        for (lit, watches) in self.watches.iter() {
+            lits += 1;
+            stats.watches += watches.len();
+            stats.binary_watches += self.binary[lit].len();
+            if watches.len() > stats.max_len {
+                stats.max_len = watches.len();
+                stats.max_lit = Some(lit);
+            }
+        }
+        if lits > 0 {
+            // the precision loss for huge watch lists is irrelevant for profiling
+            #[allow(clippy::cast_precision_loss)]
+            let mean_len = stats.watches as f64 / lits as f64;
+            stats.mean_len = mean_len;
+        }
+        stats
+    }
 }
 
 impl std::ops::Index<Lit> for WatchList {
@@ -48,3 +136,91 @@ impl std::ops::IndexMut<Lit> for WatchList {
         &mut self.watches[lit]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clause::alloc::Allocator;
+
+    #[test]
+    fn add_watch_when_enabled() {
+        let mut allocator = Allocator::default();
+        let (lit, other) = (Lit::from_dimacs(1), Lit::from_dimacs(2));
+        let clause = allocator.add(&[lit, other]);
+        let binary = BinaryWatch { clause, other, len: 2 };
+        let mut watches = WatchList::default();
+        watches.set_var_count(2);
+
+        watches.add_watch(lit, Watch { clause });
+        watches.add_binary_watch(lit, binary);
+        assert!(watches[lit].is_empty());
+        assert!(watches.binary(lit).is_empty());
+
+        watches.set_enabled();
+        watches.add_watch(lit, Watch { clause });
+        watches.add_binary_watch(lit, binary);
+        assert_eq!(watches[lit].len(), 1);
+        assert_eq!(watches.binary(lit)[0].other, other);
+
+        watches.clear();
+        assert!(!watches.enabled());
+        assert!(watches[lit].is_empty());
+        assert!(watches.binary(lit).is_empty());
+    }
+
+    #[test]
+    fn binary_symmetry() {
+        let mut allocator = Allocator::default();
+        let (a, b) = (Lit::from_dimacs(1), Lit::from_dimacs(-2));
+        let clause = allocator.add(&[a, b]);
+        let mut watches = WatchList::default();
+        watches.set_var_count(2);
+        watches.set_enabled();
+        watches.add_binary_watch(a, BinaryWatch { clause, other: b, len: 2 });
+        watches.add_binary_watch(b, BinaryWatch { clause, other: a, len: 2 });
+
+        // the clause (a, b) encodes both -a -> b and -b -> a
+        let containing_a: Vec<_> =
+            watches.iter_containing(a.var()).map(|(lit, watch)| (lit, watch.other)).collect();
+        assert_eq!(containing_a, vec![(a, b)]);
+        let containing_b: Vec<_> =
+            watches.iter_containing(b.var()).map(|(lit, watch)| (lit, watch.other)).collect();
+        assert_eq!(containing_b, vec![(b, a)]);
+        assert!(watches.iter_containing(a.var()).all(|(_, watch)| watch.clause == clause));
+
+        let mut assignment = Assignment::default();
+        assignment.set_var_count(2);
+        assert!(!watches.is_implied_true(a, &assignment));
+        assert!(!watches.is_implied_true(b, &assignment));
+
+        assignment.assign_function(!a);
+        assert!(watches.is_implied_true(b, &assignment));
+        assert!(!watches.is_implied_true(a, &assignment));
+        assert!(!watches.is_implied_true(!b, &assignment));
+
+        assignment.unassign(a.var());
+        assignment.assign_constant(!b);
+        assert!(watches.is_implied_true(a, &assignment));
+        assert!(!watches.is_implied_true(b, &assignment));
+    }
+
+    #[test]
+    fn stats() {
+        let mut allocator = Allocator::default();
+        let (a, b) = (Lit::from_dimacs(1), Lit::from_dimacs(-2));
+        let clause = allocator.add(&[a, b]);
+        let mut watches = WatchList::default();
+        watches.set_var_count(2);
+        assert_eq!(watches.stats(), WatchStats::default());
+
+        watches.set_enabled();
+        watches.add_watch(a, Watch { clause });
+        watches.add_watch(b, Watch { clause });
+        watches.add_watch(b, Watch { clause });
+        let stats = watches.stats();
+        assert_eq!(stats.watches, 3);
+        assert_eq!(stats.max_len, 2);
+        assert_eq!(stats.max_lit, Some(b));
+        assert!((stats.mean_len - 0.75).abs() < f64::EPSILON);
+    }
+}
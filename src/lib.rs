@@ -1,28 +1,56 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(unsafe_code)]
 #![deny(unused_must_use)]
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(clippy::missing_panics_doc, clippy::module_name_repetitions)]
+// without `std`, only the literal and clause types are available
+#![cfg_attr(not(feature = "std"), allow(dead_code))]
 //#![warn(clippy::cargo)]
 
-use std::{
-    fmt::Display,
-    process::{ExitCode, Termination},
-};
+extern crate alloc;
 
+use core::fmt::Display;
+#[cfg(feature = "std")]
+use std::process::{ExitCode, Termination};
+
+#[cfg(feature = "std")]
 #[macro_use]
 pub mod qcnf;
+#[cfg(feature = "std")]
+pub mod certificate;
 mod clause;
 mod literal;
+#[cfg(feature = "std")]
+pub mod portfolio;
+#[cfg(feature = "std")]
 pub mod qdimacs;
 // mod qrat;
+#[cfg(feature = "std")]
 pub mod cli;
 mod datastructure;
+#[cfg(feature = "std")]
+pub mod expand;
+#[cfg(feature = "rand")]
+pub mod generate;
+#[cfg(feature = "std")]
 pub mod incdet;
 mod quantifier;
+#[cfg(feature = "std")]
 mod sat;
+#[cfg(feature = "std")]
+mod solve;
 
 // Re-export
+#[cfg(feature = "std")]
+pub use certificate::{verify_skolem, SkolemCertificate};
+#[cfg(feature = "std")]
+pub use cli::{run_on_stdin, solve_dir};
+pub use literal::{Lit, Var};
+#[cfg(feature = "std")]
+pub use portfolio::{solve_portfolio, Engine};
 pub use quantifier::QuantTy;
+#[cfg(feature = "std")]
+pub use solve::{try_solve, SolveError};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -33,7 +61,7 @@ pub enum SolverResult {
 }
 
 impl Display for SolverResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             SolverResult::Satisfiable => write!(f, "satisfiable"),
             SolverResult::Unsatisfiable => write!(f, "unsatisfiable"),
@@ -42,6 +70,7 @@ impl Display for SolverResult {
     }
 }
 
+#[cfg(feature = "std")]
 impl Termination for SolverResult {
     fn report(self) -> ExitCode {
         ExitCode::from(self as u8)
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Var {
@@ -8,16 +8,19 @@ pub struct Var {
 impl Var {
     pub(crate) const MAX_VAR: Var = Var { index: (u32::MAX >> 1) - 1 };
 
+    #[must_use]
     pub fn from_index(index: u32) -> Self {
         assert!(index <= Self::MAX_VAR.index);
         Self { index }
     }
 
+    #[must_use]
     pub fn from_dimacs(var: i32) -> Self {
         assert!(var > 0);
         Self::from_index((var - 1).try_into().expect("var - 1 is greater or equal to 0"))
     }
 
+    #[must_use]
     pub fn to_dimacs(self) -> i32 {
         (self.index + 1).try_into().expect("index + 1 should always be smaller than i32::MAX")
     }
@@ -26,6 +29,13 @@ impl Var {
         usize::try_from(self.index).unwrap()
     }
 
+    /// Returns the 0-based index used by the solver data structures.
+    /// Use [`Var::to_dimacs`] for output.
+    #[must_use]
+    pub fn debug_index(self) -> u32 {
+        self.index
+    }
+
     pub(crate) fn positive(self) -> Lit {
         Lit::positive(self)
     }
@@ -33,11 +43,26 @@ impl Var {
     pub(crate) fn negative(self) -> Lit {
         Lit::negative(self)
     }
+
+    /// Returns the positive and negative literal of this variable (in that order).
+    pub(crate) fn both_literals(self) -> [Lit; 2] {
+        [self.positive(), self.negative()]
+    }
+
+    /// Returns an iterator over the first `count` variables.
+    pub(crate) fn range(count: usize) -> impl Iterator<Item = Var> + DoubleEndedIterator {
+        (0..count).map(|idx| Var::from_index(idx.try_into().unwrap()))
+    }
 }
 
+/// Displays the DIMACS representation, the alternate format `{:#}` displays the internal index, e.g., `v0`.
 impl Display for Var {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_dimacs())
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            write!(f, "v{}", self.debug_index())
+        } else {
+            write!(f, "{}", self.to_dimacs())
+        }
     }
 }
 
@@ -48,7 +73,7 @@ pub struct Lit {
 }
 
 #[cfg(not(kani))]
-const _: () = assert!(std::mem::size_of::<Lit>() == 4);
+const _: () = assert!(core::mem::size_of::<Lit>() == 4);
 
 impl Lit {
     pub(crate) const MIN_LIT: Lit = Lit::negative(Var::MAX_VAR);
@@ -80,14 +105,21 @@ impl Lit {
         !self.is_negative()
     }
 
+    /// Returns `true` for positive and `false` for negative literals.
+    pub(crate) fn polarity(self) -> bool {
+        self.is_positive()
+    }
+
     pub(crate) fn negated(self) -> Self {
         Self { repr: self.repr ^ 1 }
     }
 
+    #[must_use]
     pub fn from_dimacs(lit: i32) -> Self {
         Self::from_var(Var::from_dimacs(lit.abs()), lit > 0)
     }
 
+    #[must_use]
     pub fn to_dimacs(self) -> i32 {
         if self.is_negative() {
             -self.var().to_dimacs()
@@ -100,19 +132,30 @@ impl Lit {
         self.repr as usize
     }
 
-    #[allow(dead_code)]
+    /// Returns the index used by the solver data structures, i.e., `2 * var + (negative as usize)`.
+    /// Use [`Lit::to_dimacs`] for output.
+    #[must_use]
+    pub fn debug_index(self) -> usize {
+        self.as_index()
+    }
+
     pub(crate) fn from_index(idx: usize) -> Lit {
         Lit { repr: idx.try_into().expect("index should be smaller than u32::MAX") }
     }
 }
 
+/// Displays the DIMACS representation, the alternate format `{:#}` displays the internal index, e.g., `l1`.
 impl Display for Lit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_dimacs())
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            write!(f, "l{}", self.debug_index())
+        } else {
+            write!(f, "{}", self.to_dimacs())
+        }
     }
 }
 
-impl std::ops::Not for Lit {
+impl core::ops::Not for Lit {
     type Output = Self;
 
     fn not(self) -> Self::Output {
@@ -141,7 +184,7 @@ impl<'a> From<&'a [Lit]> for LitSlice<'a> {
 }
 
 impl<'a> Display for LitSlice<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         write!(f, "(")?;
         for (idx, lit) in self.0.iter().enumerate() {
             if idx > 0 {
@@ -167,6 +210,32 @@ mod test {
         assert_eq!(l, !neg_l);
     }
 
+    #[test]
+    fn polarity() {
+        let a = Var::from_dimacs(1);
+        let [pos, neg] = a.both_literals();
+        assert!(pos.polarity());
+        assert!(!neg.polarity());
+        assert_eq!(Lit::from_var(a, pos.polarity()), pos);
+        assert_eq!(Lit::from_var(a, neg.polarity()), neg);
+    }
+
+    #[test]
+    fn debug_index() {
+        let var = Var::from_dimacs(2);
+        let [pos, neg] = var.both_literals();
+        assert_eq!(var.debug_index(), 1);
+        assert_eq!((pos.debug_index(), neg.debug_index()), (2, 3));
+        assert_eq!(format!("{var} {pos} {neg}"), "2 2 -2");
+        assert_eq!(format!("{var:#} {pos:#} {neg:#}"), "v1 l2 l3");
+    }
+
+    #[test]
+    fn range() {
+        let vars: Vec<_> = Var::range(3).collect();
+        assert_eq!(vars, [Var::from_index(0), Var::from_index(1), Var::from_index(2)]);
+    }
+
     #[test]
     fn max_var() {
         let _max = Var::from_index(Var::MAX_VAR.index);
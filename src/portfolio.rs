@@ -0,0 +1,100 @@
+//! Portfolio solving, i.e., running multiple engines in parallel and taking the first answer.
+
+use crate::{expand::solve_by_expansion_with_interrupt, incdet::IncDet, qcnf::QCNF, SolverResult};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+use tracing::{debug, error};
+
+/// The solving engines available for [`solve_portfolio`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Incremental determinization, see [`IncDet`].
+    IncDet,
+    /// Universal expansion, see [`crate::expand::solve_by_expansion`].
+    Expansion,
+}
+
+/// Solves the formula with every engine in a separate thread and returns the first
+/// definite answer, i.e., [`SolverResult::Satisfiable`] or [`SolverResult::Unsatisfiable`].
+/// The remaining engines are cancelled cooperatively and keep running until they notice it.
+/// Returns [`SolverResult::Unknown`] if no engine was able to solve the formula.
+#[must_use]
+pub fn solve_portfolio(qcnf: &QCNF, engines: &[Engine]) -> SolverResult {
+    let qcnf = Arc::new(qcnf.clone());
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    for &engine in engines {
+        let qcnf = Arc::clone(&qcnf);
+        let interrupt = Arc::clone(&interrupt);
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let result = match engine {
+                Engine::IncDet => {
+                    IncDet::builder().interrupt(interrupt).build_from_qcnf(&qcnf).solve()
+                }
+                Engine::Expansion => solve_by_expansion_with_interrupt(&qcnf, &interrupt),
+            };
+            // the receiver is gone if another engine already answered
+            let _ = sender.send((engine, result));
+        });
+    }
+    drop(sender);
+
+    // the iterator ends once all engines have finished
+    for (engine, result) in receiver {
+        debug!("engine {engine:?} finished with {result}");
+        if result != SolverResult::Unknown {
+            interrupt.store(true, Ordering::Relaxed);
+            return result;
+        }
+    }
+    error!("no engine was able to solve the formula");
+    SolverResult::Unknown
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::expand::solve_by_expansion;
+    use proptest::prelude::*;
+
+    #[test]
+    fn no_engine() {
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2;
+            1 2;
+        ];
+        assert_eq!(solve_portfolio(&qcnf, &[]), SolverResult::Unknown);
+    }
+
+    #[test]
+    fn interrupted() {
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2;
+            1 2;
+        ];
+        let mut solver = IncDet::from_qcnf(&qcnf);
+        solver.set_interrupt(Arc::new(AtomicBool::new(true)));
+        assert_eq!(solver.solve(), SolverResult::Unknown);
+        assert_eq!(
+            solve_by_expansion_with_interrupt(&qcnf, &AtomicBool::new(true)),
+            SolverResult::Unknown
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+        #[test]
+        fn agrees_with_engines(qcnf in crate::qcnf::strategy::qcnf(1..=2, 1..5, 0..20, 1..5)) {
+            let result = solve_portfolio(&qcnf, &[Engine::IncDet, Engine::Expansion]);
+            assert_eq!(result, solve_by_expansion(&qcnf));
+        }
+    }
+}
@@ -1,15 +1,25 @@
 //! A straight-forward representation of a QBF in CNF.
 
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{self, Write},
+};
+
 use crate::{
+    clause::is_tautology,
+    datastructure::VarVec,
     literal::{Lit, Var},
     qdimacs::FromQdimacs,
     QuantTy,
 };
+use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct QCNF {
     pub prefix: Vec<(QuantTy, Vec<Var>)>,
     pub matrix: Vec<Vec<Lit>>,
+    /// weighted soft clauses, see [`crate::qdimacs::QdimacsParser::soft_clauses`]
+    pub soft: Vec<(u64, Vec<Lit>)>,
 }
 
 impl QCNF {
@@ -25,25 +35,482 @@ impl QCNF {
             .iter()
             .map(|&lits| lits.iter().map(|&lit| Lit::from_dimacs(lit)).collect())
             .collect();
-        QCNF { prefix, matrix }
+        QCNF { prefix, matrix, soft: Vec::new() }
+    }
+
+    /// Removes the prefix, matrix, and soft clauses while keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.prefix.clear();
+        self.matrix.clear();
+        self.soft.clear();
     }
 
     fn num_clauses(&self) -> u32 {
         self.matrix.len().try_into().unwrap()
     }
 
-    fn num_variables(&self) -> u32 {
+    pub(crate) fn num_variables(&self) -> u32 {
         self.prefix
             .iter()
             .flat_map(|(_, bound)| bound)
             .map(|var| var.to_dimacs())
-            .chain(self.matrix.iter().flatten().map(|lit| lit.to_dimacs()))
+            .chain(self.matrix.iter().flatten().map(|lit| lit.var().to_dimacs()))
+            .chain(self.soft.iter().flat_map(|(_, clause)| clause).map(|lit| lit.var().to_dimacs()))
             .max()
             .unwrap_or_default()
             .try_into()
             .unwrap()
     }
 
+    /// Returns for every variable the number of positive and negative occurrences in the matrix.
+    pub fn variable_occurrences(&self) -> impl Iterator<Item = (Var, u32, u32)> {
+        let mut occurrences: VarVec<(u32, u32)> = VarVec::default();
+        occurrences.set_var_count(self.num_variables().try_into().unwrap());
+        for &lit in self.matrix.iter().flatten() {
+            let (positive, negative) = &mut occurrences[lit.var()];
+            if lit.polarity() {
+                *positive += 1;
+            } else {
+                *negative += 1;
+            }
+        }
+        Var::range(occurrences.get_var_count()).map(move |var| {
+            let (positive, negative) = occurrences[var];
+            (var, positive, negative)
+        })
+    }
+
+    /// Writes the formula in the QDIMACS format, where clauses longer than `max_line_len`
+    /// characters are split over multiple lines.
+    /// A single literal is never split, thus, lines may exceed `max_line_len` if it is very small.
+    ///
+    /// # Errors
+    ///
+    /// Propagates failures of the underlying writer.
+    pub fn write_dimacs_wrapped<W: Write>(&self, w: &mut W, max_line_len: usize) -> io::Result<()> {
+        writeln!(w, "p cnf {} {}", self.num_variables(), self.num_clauses())?;
+        for (q, vars) in &self.prefix {
+            writeln!(
+                w,
+                "{q} {} 0",
+                vars.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+            )?;
+        }
+        let hard = self.matrix.iter().map(|clause| (None, clause));
+        let soft = self.soft.iter().map(|(weight, clause)| (Some(weight), clause));
+        for (weight, clause) in hard.chain(soft) {
+            let mut line_len = 0;
+            let weight = weight.into_iter().flat_map(|weight| ["w".to_owned(), weight.to_string()]);
+            let lits = clause.iter().map(ToString::to_string);
+            for token in weight.chain(lits).chain(std::iter::once("0".into())) {
+                if line_len > 0 && line_len + 1 + token.len() > max_line_len {
+                    writeln!(w)?;
+                    line_len = 0;
+                }
+                if line_len > 0 {
+                    write!(w, " ")?;
+                    line_len += 1;
+                }
+                write!(w, "{token}")?;
+                line_len += token.len();
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the formula as a circuit in the QCIR-G14 format, see <https://www.qbflib.org/qcir.pdf>.
+    ///
+    /// Variables keep their DIMACS numbers, gates are numbered consecutively after the largest variable:
+    /// every clause becomes an `or` gate, the output is the `and` gate over all clauses.
+    /// Variables of the matrix that are not bound by the prefix are declared `free`.
+    /// Soft clauses are not part of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Propagates failures of the underlying writer.
+    pub fn write_qcir<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "#QCIR-G14")?;
+        let bound: HashSet<Var> = self.prefix.iter().flat_map(|(_, vars)| vars).copied().collect();
+        let mut free: Vec<Var> = self
+            .matrix
+            .iter()
+            .flatten()
+            .map(|lit| lit.var())
+            .filter(|var| !bound.contains(var))
+            .collect();
+        free.sort_unstable();
+        free.dedup();
+        if !free.is_empty() {
+            writeln!(w, "free({})", comma_separated(&free))?;
+        }
+        for (q, vars) in self.prefix.iter().filter(|(_, vars)| !vars.is_empty()) {
+            let quantifier = match q {
+                QuantTy::Exists => "exists",
+                QuantTy::Forall => "forall",
+            };
+            writeln!(w, "{quantifier}({})", comma_separated(vars))?;
+        }
+
+        let first_gate = self.num_variables() + 1;
+        let output = first_gate + self.num_clauses();
+        writeln!(w, "output({output})")?;
+        for (gate, clause) in (first_gate..).zip(&self.matrix) {
+            writeln!(w, "{gate} = or({})", comma_separated(clause))?;
+        }
+        writeln!(w, "{output} = and({})", comma_separated(first_gate..output))
+    }
+
+    /// Returns the interaction graph of the formula, i.e., the variables of the prefix and matrix connected
+    /// by an edge if they occur together in a clause. Soft clauses are ignored.
+    /// The number of edges of a clause is quadratic in its number of variables.
+    #[must_use]
+    pub fn interaction_graph(&self) -> InteractionGraph {
+        let level = self.levels();
+        let mut vars: Vec<Var> = self
+            .prefix
+            .iter()
+            .flat_map(|(_, vars)| vars.iter().copied())
+            .chain(self.matrix.iter().flatten().map(|lit| lit.var()))
+            .collect();
+        vars.sort_unstable();
+        vars.dedup();
+        let nodes = vars
+            .into_iter()
+            .map(|var| {
+                let (level, quant) = level(var);
+                (var, level, quant)
+            })
+            .collect();
+
+        let mut edges: BTreeMap<(Var, Var), u32> = BTreeMap::new();
+        for clause in &self.matrix {
+            let mut vars: Vec<Var> = clause.iter().map(|lit| lit.var()).collect();
+            vars.sort_unstable();
+            vars.dedup();
+            for (idx, &first) in vars.iter().enumerate() {
+                for &second in &vars[idx + 1..] {
+                    *edges.entry((first, second)).or_default() += 1;
+                }
+            }
+        }
+        let edges =
+            edges.into_iter().map(|((first, second), count)| (first, second, count)).collect();
+        InteractionGraph { nodes, edges }
+    }
+
+    /// Compares the formulas while ignoring the order of variables within a scope,
+    /// the order and duplicates of literals within a clause, and the order of (soft) clauses.
+    /// The order of scopes and the number of occurrences of a clause matter.
+    #[must_use]
+    pub fn semantically_eq(&self, other: &QCNF) -> bool {
+        fn normalize<T: Ord + Clone>(items: &[T]) -> Vec<T> {
+            let mut items = items.to_vec();
+            items.sort_unstable();
+            items.dedup();
+            items
+        }
+        fn normalize_matrix(matrix: &[Vec<Lit>]) -> Vec<Vec<Lit>> {
+            let mut matrix: Vec<_> = matrix.iter().map(|clause| normalize(clause)).collect();
+            matrix.sort_unstable();
+            matrix
+        }
+        fn normalize_soft(soft: &[(u64, Vec<Lit>)]) -> Vec<(u64, Vec<Lit>)> {
+            let mut soft: Vec<_> =
+                soft.iter().map(|(weight, clause)| (*weight, normalize(clause))).collect();
+            soft.sort_unstable();
+            soft
+        }
+
+        self.prefix.len() == other.prefix.len()
+            && self.prefix.iter().zip(&other.prefix).all(
+                |((quant, vars), (other_quant, other_vars))| {
+                    quant == other_quant && normalize(vars) == normalize(other_vars)
+                },
+            )
+            && normalize_matrix(&self.matrix) == normalize_matrix(&other.matrix)
+            && normalize_soft(&self.soft) == normalize_soft(&other.soft)
+    }
+
+    /// Removes blocked clauses and returns the number of removed clauses.
+    ///
+    /// A clause `C` is blocked on an existential literal `l` in `C` if every clause `D` containing `!l`
+    /// contains the negation of another literal `k` of `C` whose variable is bound at the level of `l` or before,
+    /// i.e., every resolvent on `l` is a tautology respecting the quantifier dependencies.
+    /// Removing a blocked clause preserves satisfiability.
+    pub fn eliminate_blocked_clauses(&mut self) -> usize {
+        let level = self.levels();
+
+        let mut eliminated = vec![false; self.matrix.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (idx, clause) in self.matrix.iter().enumerate() {
+                if eliminated[idx] {
+                    continue;
+                }
+                let is_blocked = clause.iter().any(|&lit| {
+                    let (lit_level, quant) = level(lit.var());
+                    quant == QuantTy::Exists
+                        && self
+                            .matrix
+                            .iter()
+                            .enumerate()
+                            .filter(|&(other_idx, other)| {
+                                other_idx != idx && !eliminated[other_idx] && other.contains(&!lit)
+                            })
+                            .all(|(_, other)| {
+                                clause.iter().any(|&k| {
+                                    k.var() != lit.var()
+                                        && level(k.var()).0 <= lit_level
+                                        && other.contains(&!k)
+                                })
+                            })
+                });
+                if is_blocked {
+                    eliminated[idx] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut is_eliminated = eliminated.iter();
+        self.matrix.retain(|_| !is_eliminated.next().unwrap());
+        eliminated.into_iter().filter(|&eliminated| eliminated).count()
+    }
+
+    /// Eliminates the existential variable `var` by replacing the clauses containing it
+    /// by their resolvents on `var`, where universal reduction is applied to the resolvents
+    /// and tautological resolvents are dropped.
+    /// The variable is removed from the prefix.
+    ///
+    /// Returns `false` and keeps the formula unchanged if `var` cannot be eliminated, i.e.,
+    /// it is universal, a universal variable is bound after it, or it occurs in a soft clause.
+    pub fn eliminate_variable(&mut self, var: Var) -> bool {
+        let Some(resolvents) = self.resolvents(var) else {
+            return false;
+        };
+        self.replace_by_resolvents(var, resolvents);
+        true
+    }
+
+    /// Eliminates existential variables, see [`QCNF::eliminate_variable`], until no elimination is possible
+    /// that increases the number of clauses by at most `growth_limit`.
+    /// Returns the number of eliminated variables.
+    pub fn bounded_variable_elimination(&mut self, growth_limit: usize) -> usize {
+        let mut eliminated = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for var in Var::range(self.num_variables().try_into().unwrap()) {
+                let occurrences = self
+                    .matrix
+                    .iter()
+                    .filter(|clause| clause.iter().any(|l| l.var() == var))
+                    .count();
+                if occurrences == 0 {
+                    continue;
+                }
+                let Some(resolvents) = self.resolvents(var) else {
+                    continue;
+                };
+                if resolvents.len() > occurrences + growth_limit {
+                    continue;
+                }
+                self.replace_by_resolvents(var, resolvents);
+                eliminated += 1;
+                changed = true;
+            }
+        }
+        eliminated
+    }
+
+    /// Returns the resolvents on `var` after universal reduction, excluding tautologies,
+    /// or `None` if `var` cannot be eliminated, see [`QCNF::eliminate_variable`].
+    fn resolvents(&self, var: Var) -> Option<Vec<Vec<Lit>>> {
+        let level = self.levels();
+        let (var_level, quant) = level(var);
+        // scopes are numbered starting at 1, thus, `var_level` is the index of the next scope
+        if quant != QuantTy::Exists
+            || self.prefix[var_level..].iter().any(|(quant, _)| *quant == QuantTy::Forall)
+            || self.soft.iter().flat_map(|(_, clause)| clause).any(|lit| lit.var() == var)
+        {
+            return None;
+        }
+        // clauses containing both literals of `var` are satisfied and dropped
+        let [positive, negative] = var.both_literals().map(|lit| {
+            self.matrix
+                .iter()
+                .filter(|clause| clause.contains(&lit) && !clause.contains(&!lit))
+                .collect::<Vec<_>>()
+        });
+        let mut resolvents = Vec::new();
+        for pos in &positive {
+            for neg in &negative {
+                let mut resolvent: Vec<Lit> =
+                    pos.iter().chain(neg.iter()).copied().filter(|lit| lit.var() != var).collect();
+                resolvent.sort_unstable();
+                resolvent.dedup();
+                if is_tautology(&resolvent) {
+                    continue;
+                }
+                // universal reduction, an empty maximal level removes every universal literal
+                let max_level = resolvent
+                    .iter()
+                    .map(|lit| level(lit.var()))
+                    .filter(|&(_, quant)| quant == QuantTy::Exists)
+                    .map(|(level, _)| level)
+                    .max();
+                resolvent.retain(|lit| {
+                    let (lit_level, quant) = level(lit.var());
+                    quant == QuantTy::Exists || max_level.map_or(false, |max| lit_level <= max)
+                });
+                resolvents.push(resolvent);
+            }
+        }
+        Some(resolvents)
+    }
+
+    fn replace_by_resolvents(&mut self, var: Var, resolvents: Vec<Vec<Lit>>) {
+        self.matrix.retain(|clause| clause.iter().all(|lit| lit.var() != var));
+        self.matrix.extend(resolvents);
+        for (_, vars) in &mut self.prefix {
+            vars.retain(|&bound| bound != var);
+        }
+    }
+
+    /// Binds the free variables, i.e., variables of the matrix that are not bound by the prefix,
+    /// in an existential scope at the start of the prefix.
+    ///
+    /// Plain DIMACS files have no prefix, thus, this turns a SAT instance into an equivalent QBF with
+    /// a single existential scope. If the outermost scope is existential, the variables are added to it.
+    pub fn lift_from_cnf(&mut self) {
+        let level = self.levels();
+        let mut free: Vec<Var> = self
+            .matrix
+            .iter()
+            .chain(self.soft.iter().map(|(_, clause)| clause))
+            .flatten()
+            .map(|lit| lit.var())
+            .filter(|&var| level(var).0 == 0)
+            .collect();
+        if free.is_empty() {
+            return;
+        }
+        free.sort_unstable();
+        free.dedup();
+        match self.prefix.first_mut() {
+            Some((QuantTy::Exists, vars)) => vars.extend(free),
+            _ => self.prefix.insert(0, (QuantTy::Exists, free)),
+        }
+    }
+
+    /// Adds the clauses and soft clauses of `other` and merges its prefix into the prefix of `self`.
+    /// Both formulas share the variable namespace, i.e., variables with the same index are identified.
+    ///
+    /// The scopes of both prefixes are aligned from the outermost scope, after adjacent scopes with the same
+    /// quantifier are merged and empty scopes are removed. If the outermost quantifiers differ, the outermost
+    /// scope of `other` is placed before the prefix of `self`. Variables bound in both prefixes keep their
+    /// scope in `self`, free variables of one formula may be bound by the other.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MergeError::ConflictingQuantifier`] if a variable is bound by different quantifiers
+    /// in the two prefixes, `self` is not modified in this case.
+    pub fn merge(&mut self, other: &QCNF) -> Result<(), MergeError> {
+        let bound: HashMap<Var, QuantTy> = self
+            .prefix
+            .iter()
+            .flat_map(|(quant, vars)| vars.iter().map(|&var| (var, *quant)))
+            .collect();
+        for (quant, vars) in &other.prefix {
+            if let Some(var) =
+                vars.iter().copied().find(|var| bound.get(var).map_or(false, |q| q != quant))
+            {
+                return Err(MergeError::ConflictingQuantifier {
+                    var,
+                    ours: bound[&var],
+                    theirs: *quant,
+                });
+            }
+        }
+
+        let mut merged = coalesce(std::mem::take(&mut self.prefix));
+        let theirs = coalesce(other.prefix.clone());
+        if let (Some((ours, _)), Some((quant, _))) = (merged.first(), theirs.first()) {
+            if ours != quant {
+                merged.insert(0, (*quant, Vec::new()));
+            }
+        }
+        // both prefixes alternate, thus, the scopes at the same position have the same quantifier
+        for (idx, (quant, vars)) in theirs.into_iter().enumerate() {
+            let vars = vars.into_iter().filter(|var| !bound.contains_key(var));
+            match merged.get_mut(idx) {
+                Some((ours, merged_vars)) => {
+                    debug_assert_eq!(*ours, quant);
+                    merged_vars.extend(vars);
+                }
+                None => merged.push((quant, vars.collect())),
+            }
+        }
+        self.prefix = merged;
+        self.matrix.extend(other.matrix.iter().cloned());
+        self.soft.extend(other.soft.iter().cloned());
+        Ok(())
+    }
+
+    /// Returns a universal assignment witnessing that the formula is false, if a cheap syntactic check finds one.
+    /// `None` means that the result is unknown, i.e., a solver has to decide the formula.
+    ///
+    /// The check finds a clause without existential literals, which reduces to the empty clause by universal reduction,
+    /// or two clauses that reduce to complementary existential unit clauses.
+    /// The returned literals falsify the universal literals of these clauses.
+    #[must_use]
+    pub fn trivially_false(&self) -> Option<Vec<Lit>> {
+        let level = self.levels();
+        let falsify = |universals: Vec<Lit>| {
+            let mut assignment: Vec<Lit> = universals.into_iter().map(|lit| !lit).collect();
+            assignment.sort_unstable();
+            assignment.dedup();
+            assignment
+        };
+        // the universal literals of clauses that reduce to an existential unit clause
+        let mut units: HashMap<Lit, Vec<Lit>> = HashMap::new();
+        for clause in self.matrix.iter().filter(|clause| !is_tautology(clause)) {
+            let (mut existentials, universals): (Vec<Lit>, Vec<Lit>) =
+                clause.iter().partition(|lit| level(lit.var()).1 == QuantTy::Exists);
+            existentials.sort_unstable();
+            existentials.dedup();
+            match existentials[..] {
+                [] => return Some(falsify(universals)),
+                [unit] if universals.iter().all(|u| level(u.var()).0 > level(unit.var()).0) => {
+                    if let Some(other) = units.get(&!unit) {
+                        let combined: Vec<Lit> = universals.iter().chain(other).copied().collect();
+                        if !is_tautology(&combined) {
+                            return Some(falsify(combined));
+                        }
+                    }
+                    units.entry(unit).or_insert(universals);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Returns a function that maps every variable to its quantifier and level, i.e., the outermost scope has level 1.
+    /// Free variables are existentially quantified in the outermost scope at level 0.
+    fn levels(&self) -> impl Fn(Var) -> (usize, QuantTy) {
+        let mut levels: VarVec<Option<(usize, QuantTy)>> = VarVec::default();
+        levels.set_var_count(self.num_variables().try_into().unwrap());
+        for (idx, (quant, vars)) in self.prefix.iter().enumerate() {
+            for &var in vars {
+                levels[var] = Some((idx + 1, *quant));
+            }
+        }
+        move |var: Var| levels.get(var).copied().flatten().unwrap_or((0, QuantTy::Exists))
+    }
+
     #[allow(dead_code)]
     pub(crate) fn is_2qbf(&self) -> bool {
         matches!(&self.prefix[..], &[(QuantTy::Forall, _), (QuantTy::Exists, _)])
@@ -62,6 +529,107 @@ impl FromQdimacs for QCNF {
     fn add_clause(&mut self, lits: &[Lit]) {
         self.matrix.push(lits.to_owned());
     }
+
+    fn add_soft_clause(&mut self, weight: u64, lits: &[Lit]) {
+        self.soft.push((weight, lits.to_owned()));
+    }
+}
+
+/// The variables of a formula connected by an edge if they occur together in a clause,
+/// see [`QCNF::interaction_graph`]. It is displayed in the DOT format, the scopes are clusters of nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InteractionGraph {
+    nodes: Vec<(Var, usize, QuantTy)>,
+    edges: Vec<(Var, Var, u32)>,
+}
+
+impl InteractionGraph {
+    /// Returns every variable with its level and quantifier, sorted by variable.
+    /// The outermost scope has level 1, free variables are existential at level 0.
+    #[must_use]
+    pub fn nodes(&self) -> &[(Var, usize, QuantTy)] {
+        &self.nodes
+    }
+
+    /// Returns the edges with the number of clauses containing both variables,
+    /// the smaller variable comes first and the edges are sorted.
+    #[must_use]
+    pub fn edges(&self) -> &[(Var, Var, u32)] {
+        &self.edges
+    }
+
+    /// Returns the variables connected to `var`, sorted by variable.
+    pub fn neighbors(&self, var: Var) -> impl Iterator<Item = Var> + '_ {
+        let mut neighbors: Vec<Var> = self
+            .edges
+            .iter()
+            .filter_map(|&(first, second, _)| {
+                if first == var {
+                    Some(second)
+                } else if second == var {
+                    Some(first)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.into_iter()
+    }
+}
+
+impl std::fmt::Display for InteractionGraph {
+    /// Writes the graph in the DOT format, edges shared by multiple clauses have a `weight`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "graph interaction {{")?;
+        let mut scopes: BTreeMap<usize, (QuantTy, Vec<Var>)> = BTreeMap::new();
+        for &(var, level, quant) in &self.nodes {
+            scopes.entry(level).or_insert_with(|| (quant, Vec::new())).1.push(var);
+        }
+        for (level, (quant, vars)) in scopes {
+            writeln!(f, "  subgraph cluster_{level} {{")?;
+            if level == 0 {
+                writeln!(f, "    label=\"free\";")?;
+            } else {
+                writeln!(f, "    label=\"{level}: {quant}\";")?;
+            }
+            for var in vars {
+                writeln!(f, "    {var};")?;
+            }
+            writeln!(f, "  }}")?;
+        }
+        for &(first, second, count) in &self.edges {
+            if count > 1 {
+                writeln!(f, "  {first} -- {second} [weight={count}];")?;
+            } else {
+                writeln!(f, "  {first} -- {second};")?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The error of [`QCNF::merge`].
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error("Variable {var} is quantified by {ours:?} and {theirs:?} in the merged prefixes")]
+    ConflictingQuantifier { var: Var, ours: QuantTy, theirs: QuantTy },
+}
+
+/// Removes empty scopes and merges adjacent scopes with the same quantifier.
+fn coalesce(prefix: Vec<(QuantTy, Vec<Var>)>) -> Vec<(QuantTy, Vec<Var>)> {
+    let mut coalesced: Vec<(QuantTy, Vec<Var>)> = Vec::with_capacity(prefix.len());
+    for (quant, vars) in prefix.into_iter().filter(|(_, vars)| !vars.is_empty()) {
+        match coalesced.last_mut() {
+            Some((last, last_vars)) if *last == quant => last_vars.extend(vars),
+            _ => coalesced.push((quant, vars)),
+        }
+    }
+    coalesced
+}
+
+fn comma_separated<T: std::fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    items.into_iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", ")
 }
 
 impl std::fmt::Display for QCNF {
@@ -80,6 +648,13 @@ impl std::fmt::Display for QCNF {
             }
             writeln!(f, "0")?;
         }
+        for (weight, clause) in &self.soft {
+            write!(f, "w {weight} ")?;
+            for lit in clause {
+                write!(f, "{lit} ")?;
+            }
+            writeln!(f, "0")?;
+        }
         Ok(())
     }
 }
@@ -159,7 +734,7 @@ pub(crate) mod strategy {
                 collection::vec(collection::vec(lit(0..max_var_idx), clause_len), clauses).prop_map(
                     move |matrix| {
                         let prefix = prefix.clone();
-                        QCNF { prefix, matrix }
+                        QCNF { prefix, matrix, soft: Vec::new() }
                     },
                 )
             })
@@ -200,6 +775,106 @@ pub(crate) mod strategy {
 
 #[cfg(test)]
 mod test {
+    use crate::{
+        literal::{Lit, Var},
+        qdimacs::QdimacsParser,
+    };
+    use proptest::prelude::*;
+
+    #[test]
+    fn interaction_graph() {
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2 3;
+            1 -2;
+            -1 2 4;
+            2 -1;
+        ];
+        let graph = qcnf.interaction_graph();
+        assert_eq!(
+            graph.neighbors(Var::from_dimacs(2)).collect::<Vec<_>>(),
+            [1, 4].map(Var::from_dimacs)
+        );
+        assert_eq!(graph.neighbors(Var::from_dimacs(3)).count(), 0);
+        assert_eq!(
+            graph.to_string(),
+            "graph interaction {
+  subgraph cluster_0 {
+    label=\"free\";
+    4;
+  }
+  subgraph cluster_1 {
+    label=\"1: a\";
+    1;
+  }
+  subgraph cluster_2 {
+    label=\"2: e\";
+    2;
+    3;
+  }
+  1 -- 2 [weight=3];
+  1 -- 4;
+  2 -- 4;
+}
+"
+        );
+    }
+
+    #[test]
+    fn write_qcir() -> std::io::Result<()> {
+        let mut qcnf = qcnf_formula![
+            a 1 2;
+            e 3;
+            1 -3;
+            -2 3 4;
+        ];
+        let mut qcir = Vec::new();
+        qcnf.write_qcir(&mut qcir)?;
+        assert_eq!(
+            String::from_utf8(qcir).unwrap(),
+            "#QCIR-G14\nfree(4)\nforall(1, 2)\nexists(3)\noutput(7)\n5 = or(1, -3)\n6 = or(-2, 3, 4)\n7 = and(5, 6)\n"
+        );
+
+        // the empty matrix is true, the empty clause is false
+        qcnf.matrix = vec![vec![]];
+        let mut qcir = Vec::new();
+        qcnf.write_qcir(&mut qcir)?;
+        assert_eq!(
+            String::from_utf8(qcir).unwrap(),
+            "#QCIR-G14\nforall(1, 2)\nexists(3)\noutput(5)\n4 = or()\n5 = and(4)\n"
+        );
+        qcnf.matrix.clear();
+        let mut qcir = Vec::new();
+        qcnf.write_qcir(&mut qcir)?;
+        assert_eq!(
+            String::from_utf8(qcir).unwrap(),
+            "#QCIR-G14\nforall(1, 2)\nexists(3)\noutput(4)\n4 = and()\n"
+        );
+
+        // the gates follow the largest variable, even if it only occurs negatively
+        let qcnf = qcnf_formula![
+            e 1;
+            1 -3;
+        ];
+        let mut qcir = Vec::new();
+        qcnf.write_qcir(&mut qcir)?;
+        assert_eq!(
+            String::from_utf8(qcir).unwrap(),
+            "#QCIR-G14\nfree(3)\nexists(1)\noutput(5)\n4 = or(1, -3)\n5 = and(4)\n"
+        );
+        let qcnf = super::QCNF {
+            prefix: Vec::new(),
+            matrix: vec![vec![Lit::from_dimacs(-1)]],
+            soft: Vec::new(),
+        };
+        let mut qcir = Vec::new();
+        qcnf.write_qcir(&mut qcir)?;
+        assert_eq!(
+            String::from_utf8(qcir).unwrap(),
+            "#QCIR-G14\nfree(1)\noutput(3)\n2 = or(-1)\n3 = and(2)\n"
+        );
+        Ok(())
+    }
 
     #[test]
     fn qcnf_macro() {
@@ -211,4 +886,319 @@ mod test {
         assert_eq!(qcnf.num_clauses(), 1);
         assert_eq!(qcnf.num_variables(), 3);
     }
+
+    proptest! {
+        #[test]
+        fn wrapped_roundtrip(
+            qcnf in super::strategy::qcnf(1..4, 1..10, 0..20, 0..30),
+            max_line_len in 1..20_usize,
+        ) {
+            let mut wrapped = Vec::new();
+            qcnf.write_dimacs_wrapped(&mut wrapped, max_line_len)?;
+            let parsed: super::QCNF = QdimacsParser::new(wrapped.as_slice()).parse()?;
+            assert_eq!(parsed, qcnf);
+        }
+    }
+
+    #[test]
+    fn wrapped() -> std::io::Result<()> {
+        let qcnf = qcnf_formula![
+            e 1 2 3 4;
+            1 -2 3 -4;
+            1;
+        ];
+        let mut wrapped = Vec::new();
+        qcnf.write_dimacs_wrapped(&mut wrapped, 6)?;
+        assert_eq!(
+            String::from_utf8(wrapped).unwrap(),
+            "p cnf 4 2\ne 1 2 3 4 0\n1 -2 3\n-4 0\n1 0\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn semantically_eq() {
+        let qcnf = qcnf_formula![
+            a 1 2;
+            e 3;
+            1 -3;
+            2 3;
+            2 3;
+        ];
+        let reordered = qcnf_formula![
+            a 2 1;
+            e 3;
+            3 2;
+            -3 1 1;
+            2 3;
+        ];
+        assert!(qcnf.semantically_eq(&reordered));
+        assert_ne!(qcnf, reordered);
+
+        // the number of clause occurrences matters
+        let fewer = qcnf_formula![a 1 2; e 3; 1 -3; 2 3;];
+        assert!(!qcnf.semantically_eq(&fewer));
+        // the order of scopes matters
+        let swapped = qcnf_formula![e 3; a 1 2; 1 -3; 2 3; 2 3;];
+        assert!(!qcnf.semantically_eq(&swapped));
+    }
+
+    #[test]
+    fn blocked_clauses() {
+        // the resolvent on 2 is tautological on the outer universal 1
+        let mut qcnf = qcnf_formula![
+            a 1;
+            e 2;
+            1 2;
+            -1 -2;
+        ];
+        assert_eq!(qcnf.eliminate_blocked_clauses(), 2);
+        assert!(qcnf.matrix.is_empty());
+
+        // the universal 1 is bound after 2, thus, the clauses are not blocked
+        let mut qcnf = qcnf_formula![
+            e 2;
+            a 1;
+            1 2;
+            -1 -2;
+        ];
+        assert_eq!(qcnf.eliminate_blocked_clauses(), 0);
+
+        // resolving the unit clauses yields the empty clause
+        let mut qcnf = qcnf_formula![
+            e 1 2;
+            1;
+            -1;
+            1 2;
+        ];
+        assert_eq!(qcnf.eliminate_blocked_clauses(), 1);
+        assert_eq!(qcnf, qcnf_formula![e 1 2; 1; -1;]);
+    }
+
+    proptest! {
+        #[test]
+        fn blocked_clauses_preserve_result(qcnf in super::strategy::qcnf(1..3, 1..5, 0..20, 1..4)) {
+            let mut reduced = qcnf.clone();
+            reduced.eliminate_blocked_clauses();
+            prop_assert_eq!(
+                crate::expand::solve_by_expansion(&reduced),
+                crate::expand::solve_by_expansion(&qcnf)
+            );
+        }
+    }
+
+    #[test]
+    fn variable_elimination() {
+        // eliminating 3 replaces its four clauses by two resolvents
+        let mut qcnf = qcnf_formula![
+            a 1;
+            e 2 3;
+            1 3;
+            2 3;
+            -3 -1;
+            -3 -2;
+        ];
+        assert!(qcnf.eliminate_variable(crate::literal::Var::from_dimacs(3)));
+        assert!(qcnf.semantically_eq(&qcnf_formula![a 1; e 2; 1 -2; 2 -1;]));
+
+        // the universal 2 is reduced from the resolvent, as 3 is the only existential bound after it
+        let mut qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            e 3;
+            1 2 3;
+            -3;
+        ];
+        assert!(qcnf.eliminate_variable(crate::literal::Var::from_dimacs(3)));
+        assert!(qcnf.semantically_eq(&qcnf_formula![e 1; a 2; e; 1;]));
+
+        // universal variables and existentials followed by a universal scope are not eliminated
+        let mut qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            e 3;
+            1 2 3;
+            -1 -3;
+        ];
+        let original = qcnf.clone();
+        assert!(!qcnf.eliminate_variable(crate::literal::Var::from_dimacs(1)));
+        assert!(!qcnf.eliminate_variable(crate::literal::Var::from_dimacs(2)));
+        assert_eq!(qcnf, original);
+    }
+
+    #[test]
+    fn bounded_variable_elimination() {
+        let mut qcnf = qcnf_formula![
+            a 1;
+            e 2 3 4;
+            1 2;
+            -2 3;
+            -3 4;
+            -4 -1;
+        ];
+        // the last resolvent is tautological on the universal 1
+        assert_eq!(qcnf.bounded_variable_elimination(0), 3);
+        assert!(qcnf.matrix.is_empty());
+        assert_eq!(qcnf.prefix[1].1, []);
+
+        // eliminating 1 yields nine resolvents for six clauses
+        let mut qcnf = qcnf_formula![
+            a 2 3 4 5 6 7;
+            e 1;
+            1 2;
+            1 3;
+            1 4;
+            -1 5;
+            -1 6;
+            -1 7;
+        ];
+        assert_eq!(qcnf.bounded_variable_elimination(0), 0);
+        assert_eq!(qcnf.bounded_variable_elimination(3), 1);
+        assert_eq!(qcnf.matrix.len(), 9);
+    }
+
+    proptest! {
+        #[test]
+        fn variable_elimination_preserves_result(
+            qcnf in super::strategy::qcnf(1..3, 1..5, 0..20, 1..4),
+            growth_limit in 0..3_usize,
+        ) {
+            let mut reduced = qcnf.clone();
+            reduced.bounded_variable_elimination(growth_limit);
+            prop_assert_eq!(
+                crate::expand::solve_by_expansion(&reduced),
+                crate::expand::solve_by_expansion(&qcnf)
+            );
+        }
+    }
+
+    #[test]
+    fn trivially_false() {
+        let lits = |lits: &[i32]| lits.iter().map(|&l| Lit::from_dimacs(l)).collect::<Vec<_>>();
+        // the clause only contains universal literals
+        let qcnf = qcnf_formula![
+            a 1 2;
+            e 3;
+            1 3;
+            2 -1;
+        ];
+        assert_eq!(qcnf.trivially_false(), Some(lits(&[1, -2])));
+
+        // the clauses reduce to the complementary units 1 and -1
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2 3;
+            1 2;
+            -1 3 2;
+        ];
+        assert_eq!(qcnf.trivially_false(), Some(lits(&[-2, -3])));
+
+        // the universals are bound before the existential, thus, the clauses are not reduced
+        let qcnf = qcnf_formula![
+            a 2;
+            e 1;
+            1 2;
+            -1 2;
+        ];
+        assert_eq!(qcnf.trivially_false(), None);
+        // the universal assignment cannot falsify both clauses
+        let qcnf = qcnf_formula![
+            e 1;
+            a 2;
+            1 2;
+            -1 -2;
+        ];
+        assert_eq!(qcnf.trivially_false(), None);
+    }
+
+    #[test]
+    fn lift_from_cnf() -> Result<(), crate::qdimacs::ParseError> {
+        let input = "c plain DIMACS\np cnf 3 4\n1 2 0\n-1 3 0\n-2 -3 0\n-3 2 0\n";
+        let mut qcnf: super::QCNF = QdimacsParser::new(std::io::Cursor::new(input)).parse()?;
+        assert!(qcnf.prefix.is_empty());
+        qcnf.lift_from_cnf();
+        assert_eq!(qcnf, qcnf_formula![e 1 2 3; 1 2; -1 3; -2 -3; -3 2;]);
+        let mut solver = crate::incdet::IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), crate::SolverResult::Satisfiable);
+
+        let mut qcnf: super::QCNF =
+            QdimacsParser::new(std::io::Cursor::new("p cnf 1 2\n1 0\n-1 0\n")).parse()?;
+        qcnf.lift_from_cnf();
+        let mut solver = crate::incdet::IncDet::from_qcnf(&qcnf);
+        assert_eq!(solver.solve(), crate::SolverResult::Unsatisfiable);
+
+        // free variables are added to an outermost existential scope
+        let mut qcnf = qcnf_formula![e 2; a 3; e 4; 1 2 3 4;];
+        qcnf.lift_from_cnf();
+        assert_eq!(qcnf, qcnf_formula![e 2 1; a 3; e 4; 1 2 3 4;]);
+        let mut qcnf = qcnf_formula![a 3; e 4; 1 3 4;];
+        qcnf.lift_from_cnf();
+        assert_eq!(qcnf, qcnf_formula![e 1; a 3; e 4; 1 3 4;]);
+        Ok(())
+    }
+
+    #[test]
+    fn merge() {
+        // shared variables keep their scope, the remaining scopes are aligned from the outermost
+        let mut qcnf = qcnf_formula![a 1; e 2; 1 2;];
+        qcnf.merge(&qcnf_formula![a 1 3; e 4; a 5; e 6; -1 3 4; 5 6 2;]).unwrap();
+        assert_eq!(qcnf, qcnf_formula![a 1 3; e 2 4; a 5; e 6; 1 2; -1 3 4; 5 6 2;]);
+
+        // the outermost scope of other is placed first, adjacent and empty scopes are coalesced
+        let mut qcnf = qcnf_formula![a 1; a 2; e 3; 1 2 3;];
+        qcnf.merge(&qcnf_formula![e 4; a 1; e 5; 4 1 5;]).unwrap();
+        assert_eq!(qcnf, qcnf_formula![e 4; a 1 2; e 3 5; 1 2 3; 4 1 5;]);
+
+        let mut qcnf = qcnf_formula![a 1; e 2; 1 2;];
+        let before = qcnf.clone();
+        let err = qcnf.merge(&qcnf_formula![e 1; a 3; 1 3;]).unwrap_err();
+        let super::MergeError::ConflictingQuantifier { var, ours, theirs } = err;
+        assert_eq!(var, crate::Var::from_dimacs(1));
+        assert_eq!((ours, theirs), (crate::QuantTy::Forall, crate::QuantTy::Exists));
+        assert_eq!(qcnf, before);
+    }
+
+    proptest! {
+        #[test]
+        fn trivially_false_is_unsat(qcnf in super::strategy::qcnf(1..3, 1..5, 0..20, 1..4)) {
+            if qcnf.trivially_false().is_some() {
+                prop_assert_eq!(
+                    crate::expand::solve_by_expansion(&qcnf),
+                    crate::SolverResult::Unsatisfiable
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn variable_occurrences() {
+        let qcnf = qcnf_formula![
+            a 1;
+            e 2 3;
+            1 2;
+            -1 2;
+            -2 1;
+        ];
+        let occurrences: Vec<_> = qcnf
+            .variable_occurrences()
+            .map(|(var, positive, negative)| (var.to_dimacs(), positive, negative))
+            .collect();
+        assert_eq!(occurrences, [(1, 2, 1), (2, 2, 1), (3, 0, 0)]);
+
+        // the largest variable only occurs negatively
+        let qcnf: super::QCNF =
+            QdimacsParser::new(std::io::Cursor::new("p cnf 5 1\ne 1 0\n1 -5 0\n")).parse().unwrap();
+        assert_eq!(qcnf.num_variables(), 5);
+        let occurrences: Vec<_> = qcnf
+            .variable_occurrences()
+            .filter(|&(_, positive, negative)| positive + negative > 0)
+            .map(|(var, positive, negative)| (var.to_dimacs(), positive, negative))
+            .collect();
+        assert_eq!(occurrences, [(1, 1, 0), (5, 0, 1)]);
+
+        // as well as in soft clauses
+        let mut qcnf = qcnf;
+        qcnf.soft.push((2, vec![Lit::from_dimacs(-7)]));
+        assert_eq!(qcnf.num_variables(), 7);
+    }
 }
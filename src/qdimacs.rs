@@ -7,11 +7,14 @@ use crate::{
 };
 use miette::{Diagnostic, SourceSpan};
 use std::{
-    io::{Bytes, Read},
+    io::{BufReader, Bytes, Read},
     iter::Peekable,
 };
 use thiserror::Error;
 
+/// The UTF-8 encoding of the byte order mark, which may precede the header.
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("Cannot parse QDIMACS")]
 #[diagnostic()]
@@ -53,8 +56,25 @@ pub enum ParseError {
         err_span: SourceSpan,
     },
 
-    #[error("Invalid integer")]
+    #[error("Quantifier prefix line after the start of the matrix")]
+    #[diagnostic(help("enable `relaxed_prefix` to accept prefix lines between clauses"))]
+    PrefixAfterMatrix {
+        #[label]
+        err_span: SourceSpan,
+    },
+
+    #[error("Invalid integer `{token}`")]
     InvalidInt {
+        /// the text of the malformed token
+        token: String,
+
+        #[label]
+        err_span: SourceSpan,
+    },
+
+    #[error("Invalid weight of soft clause")]
+    #[diagnostic(help("weights have to be non-negative integers"))]
+    InvalidWeight {
         #[label]
         err_span: SourceSpan,
     },
@@ -70,22 +90,41 @@ pub enum ParseError {
     #[error("Literal {val} is out of bound")]
     LiteralOutOfBound {
         val: i64,
+        /// the text of the parsed token
+        token: String,
 
         #[label]
         err_span: SourceSpan,
     },
 
     #[error(
-        "Number of clauses does not match header: expected {}, but found {} clauses",
+        "Number of clauses does not match header: expected {}, but found {} clauses{}",
         expected,
-        found
+        found,
+        if *at_eof { " before the input ended inside a truncated clause" } else { "" }
     )]
-    NumClausesMismatch { expected: u32, found: u32 },
+    NumClausesMismatch {
+        expected: u32,
+        found: u32,
+        /// whether the input ended inside a clause, i.e., the last clause is truncated
+        /// instead of missing
+        at_eof: bool,
+    },
+
+    #[error("Clause has more than {max_width} literals")]
+    #[diagnostic(help("the parser is configured to reject wide clauses, see `max_clause_width`"))]
+    ClauseTooWide {
+        /// the configured maximal number of literals
+        max_width: usize,
+
+        #[label("clause exceeds the maximal width")]
+        err_span: SourceSpan,
+    },
 }
 
 #[derive(Debug, Error, Diagnostic)]
 pub enum HeaderError {
-    #[error("`p cnf` prefix missing or invalid")]
+    #[error("`p cnf` or `p qcnf` prefix missing or invalid")]
     InvalidPrefix,
 
     #[error("Invalid variable count")]
@@ -102,20 +141,178 @@ pub trait FromQdimacs: Default {
     fn set_num_clauses(&mut self, clauses: u32);
     fn quantify(&mut self, quant: QuantTy, vars: &[Var]);
     fn add_clause(&mut self, lits: &[Lit]);
+
+    /// Adds a clause that starts in the (1-based) `line` of the input, only called if the parser
+    /// tracks source lines (see [`QdimacsParser::source_lines`]). Delegates to [`FromQdimacs::add_clause`] by default.
+    fn add_clause_at(&mut self, lits: &[Lit], _line: usize) {
+        self.add_clause(lits);
+    }
+
+    /// Adds a scope like [`FromQdimacs::quantify`] together with the span of every variable in the input,
+    /// only called if the parser tracks source spans (see [`QdimacsParser::source_spans`]).
+    /// Delegates to [`FromQdimacs::quantify`] by default.
+    fn quantify_spanned(&mut self, quant: QuantTy, vars: &[Var], _spans: &[SourceSpan]) {
+        self.quantify(quant, vars);
+    }
+
+    /// Adds a clause together with the span of every literal in the input, only called if the parser
+    /// tracks source spans (see [`QdimacsParser::source_spans`]). Delegates to [`FromQdimacs::add_clause`] by default.
+    fn add_clause_spanned(&mut self, lits: &[Lit], _spans: &[SourceSpan]) {
+        self.add_clause(lits);
+    }
+
+    /// Adds a soft clause `w <weight> <lits> 0`, only called if the parser accepts soft clauses
+    /// (see [`QdimacsParser::soft_clauses`]). Soft clauses are ignored by default.
+    fn add_soft_clause(&mut self, _weight: u64, _lits: &[Lit]) {}
+}
+
+/// Scratch space used while parsing a single prefix line or clause.
+///
+/// The buffer can be moved between parser instances (see [`QdimacsParser::with_buffer`]
+/// and [`QdimacsParser::into_buffer`]) to avoid reallocations when parsing many files.
+#[derive(Debug, Clone, Default)]
+pub struct ParseBuffer {
+    vars: Vec<Var>,
+    lits: Vec<Lit>,
+    /// the spans of the parsed variables or literals, if source spans are tracked
+    spans: Vec<SourceSpan>,
+    /// the bytes of the last parsed integer
+    token: Vec<u8>,
 }
 
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct QdimacsParser<R: Read> {
-    bytes: Peekable<Bytes<R>>,
+    /// the input is buffered, as it is consumed byte by byte
+    bytes: Peekable<Bytes<BufReader<R>>>,
+    num_variables: u32,
     num_clauses: u32,
     num_clauses_read: u32,
+    /// accept quantifier prefix lines after the first clause
+    relaxed_prefix: bool,
+    /// accept weighted soft clauses `w <weight> <lits> 0`
+    soft_clauses: bool,
+    /// the collected comment lines, `None` if comments are skipped
+    comments: Option<Vec<String>>,
+    /// report the line of every clause by [`FromQdimacs::add_clause_at`]
+    source_lines: bool,
+    /// report the span of every variable and literal, see [`QdimacsParser::source_spans`]
+    source_spans: bool,
+    /// reject clauses with more literals
+    max_clause_width: Option<usize>,
+    /// reject inputs that do not conform to the header, see [`QdimacsParser::strict`]
+    strict: bool,
+    buffer: ParseBuffer,
 
     offset: usize,
+    /// the current line, starting at 1
+    line: usize,
 }
 
 impl<R: Read> QdimacsParser<R> {
+    /// Creates a parser reading from `reader`.
+    /// The reader is wrapped in a [`BufReader`], thus, it does not need to be buffered by the caller.
     pub fn new(reader: R) -> Self {
-        Self { bytes: reader.bytes().peekable(), offset: 0, num_clauses: 0, num_clauses_read: 0 }
+        Self::with_buffer(reader, ParseBuffer::default())
+    }
+
+    /// Creates a parser that reuses the allocations of a previous parser.
+    pub fn with_buffer(reader: R, buffer: ParseBuffer) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes().peekable(),
+            offset: 0,
+            line: 1,
+            num_variables: 0,
+            num_clauses: 0,
+            num_clauses_read: 0,
+            relaxed_prefix: false,
+            soft_clauses: false,
+            comments: None,
+            source_lines: false,
+            source_spans: false,
+            max_clause_width: None,
+            strict: false,
+            buffer,
+        }
+    }
+
+    /// Collects the comment lines (`c ...`) instead of skipping them, see [`Self::comments`].
+    #[must_use]
+    pub fn collect_comments(mut self, enabled: bool) -> Self {
+        self.comments = enabled.then(Vec::new);
+        self
+    }
+
+    /// Returns the text of the comment lines read so far, without the leading `c`.
+    /// The result is empty unless comments are collected (see [`Self::collect_comments`]).
+    pub fn comments(&self) -> &[String] {
+        self.comments.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the comment lines of the form `c <key> <value>` as key-value pairs.
+    pub fn comment_metadata(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.comments().iter().filter_map(|comment| {
+            let (key, value) = comment.split_once(|c: char| c.is_ascii_whitespace())?;
+            let value = value.trim();
+            (!value.is_empty()).then_some((key, value))
+        })
+    }
+
+    /// Consumes the parser and returns its scratch buffer for reuse.
+    pub fn into_buffer(self) -> ParseBuffer {
+        self.buffer
+    }
+
+    /// Accepts quantifier prefix lines (`e ...` or `a ...`) that appear after the
+    /// matrix has started, as produced by some legacy tools.
+    /// The late scopes are merged into the prefix before any clause is handed to
+    /// the result representation.
+    #[must_use]
+    pub fn relaxed_prefix(mut self, enabled: bool) -> Self {
+        self.relaxed_prefix = enabled;
+        self
+    }
+
+    /// Reports the line in which every clause starts by [`FromQdimacs::add_clause_at`]
+    /// instead of [`FromQdimacs::add_clause`], e.g., to refer to the input in diagnostics.
+    #[must_use]
+    pub fn source_lines(mut self, enabled: bool) -> Self {
+        self.source_lines = enabled;
+        self
+    }
+
+    /// Reports the span of every variable of the prefix by [`FromQdimacs::quantify_spanned`] and of every literal
+    /// of a clause by [`FromQdimacs::add_clause_spanned`], e.g., to label the tokens in diagnostics.
+    /// Takes precedence over [`QdimacsParser::source_lines`].
+    #[must_use]
+    pub fn source_spans(mut self, enabled: bool) -> Self {
+        self.source_spans = enabled;
+        self
+    }
+
+    /// Rejects clauses with more than `max_width` literals with [`ParseError::ClauseTooWide`],
+    /// e.g., to guard against pathological instances. Clauses are unlimited by default.
+    #[must_use]
+    pub fn max_clause_width(mut self, max_width: usize) -> Self {
+        self.max_clause_width = Some(max_width);
+        self
+    }
+
+    /// Rejects variables in the prefix or matrix that exceed the variable count of the header
+    /// with [`ParseError::VariableOutOfBound`]. By default, only the representable range is checked.
+    #[must_use]
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Accepts weighted soft clauses of the form `w <weight> <lits> 0` in the matrix,
+    /// which are reported by [`FromQdimacs::add_soft_clause`].
+    /// Soft clauses do not count towards the number of clauses in the header.
+    #[must_use]
+    pub fn soft_clauses(mut self, enabled: bool) -> Self {
+        self.soft_clauses = enabled;
+        self
     }
 
     /// Parses a QDIMACS file and returns the representation `Q`.
@@ -126,48 +323,97 @@ impl<R: Read> QdimacsParser<R> {
     /// The function propagates underlying IO failures.
     pub fn parse<Q: FromQdimacs>(&mut self) -> Result<Q, ParseError> {
         let mut result = Q::default();
-        self.parse_comment_or_header(&mut result)?;
-        self.parse_prefix(&mut result)?;
-        self.parse_matrix(&mut result)?;
+        self.parse_into(&mut result)?;
+        Ok(result)
+    }
+
+    /// Parses a QDIMACS file into an existing representation `Q`.
+    /// This allows to reuse `result` when parsing many files, `result` is
+    /// expected to be empty, i.e., in the same state as [`Default::default`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the read content is not valid QDIMACS.
+    /// The function propagates underlying IO failures.
+    pub fn parse_into<Q: FromQdimacs>(&mut self, result: &mut Q) -> Result<(), ParseError> {
+        self.parse_comment_or_header(result)?;
+        if self.relaxed_prefix {
+            // clauses may reference variables bound by later prefix lines,
+            // thus, the formula is buffered until the complete prefix is known
+            let mut deferred = DeferredFormula::default();
+            self.parse_prefix(&mut deferred)?;
+            self.parse_matrix(&mut deferred)?;
+            deferred.replay(result);
+        } else {
+            self.parse_prefix(result)?;
+            self.parse_matrix(result)?;
+        }
 
         // check that number of clauses match the header
         if self.num_clauses_read != self.num_clauses {
             return Err(ParseError::NumClausesMismatch {
                 expected: self.num_clauses,
                 found: self.num_clauses_read,
+                at_eof: false,
             });
         }
 
-        Ok(result)
+        Ok(())
     }
 
-    /// Either `c ...` or `p cnf ...`
+    /// Either `c ...` or `p cnf ...` (respectively `p qcnf ...`)
     fn parse_comment_or_header<Q: FromQdimacs>(
         &mut self,
         result: &mut Q,
     ) -> Result<(), ParseError> {
+        if self.offset == 0 && self.peek_byte() == Some(UTF8_BOM[0]) {
+            // skip the byte order mark written by some Windows editors
+            self.expect(&UTF8_BOM)?;
+        }
         while let Some(b) = self.next_byte()? {
             match b {
                 b'c' => {
-                    // start of a comment line, ignore remaining line
-                    self.skip_until(b'\n')?;
+                    if self.comments.is_some() {
+                        let comment = self.read_until(b'\n')?;
+                        let comment = String::from_utf8_lossy(&comment).trim().to_owned();
+                        self.comments.get_or_insert_with(Vec::new).push(comment);
+                    } else {
+                        // start of a comment line, ignore remaining line
+                        self.skip_until(b'\n')?;
+                    }
                 }
                 b'p' => {
-                    // `p cnf [NUM_VARIABLES] [NUM_CLAUSES]` header
-                    self.expect(&b" cnf"[..]).map_err(|_| ParseError::InvalidHeader {
-                        reason: HeaderError::InvalidPrefix,
-                        err_span: self.err_span(),
-                    })?;
+                    // `p cnf [NUM_VARIABLES] [NUM_CLAUSES]` header,
+                    // some tools emit `p qcnf` instead
+                    self.expect(&b" "[..])
+                        .and_then(|()| {
+                            if self.peek_byte() == Some(b'q') {
+                                self.next_byte()?;
+                            }
+                            self.expect(&b"cnf"[..])
+                        })
+                        .map_err(|_| ParseError::InvalidHeader {
+                            reason: HeaderError::InvalidPrefix,
+                            err_span: self.err_span(),
+                        })?;
 
                     // parse variable count
                     self.skip_whitespace_and_peek()?.ok_or_else(|| {
                         ParseError::UnexpectedEndOfFile { err_span: self.err_span() }
                     })?;
+                    let start_offset = self.err_offset();
                     let num_variables: u32 =
                         self.parse_int().map_err(|err| ParseError::InvalidHeader {
                             reason: HeaderError::InvalidVariableCount,
                             err_span: err.err_span().unwrap_or_else(|| self.err_span()),
                         })?;
+                    if num_variables > Var::MAX_VAR.to_dimacs().unsigned_abs() {
+                        return Err(ParseError::VariableOutOfBound {
+                            val: num_variables.into(),
+                            // reduce end offset by one, as last byte was a whitespace
+                            err_span: (start_offset..self.err_offset().saturating_sub(1)).into(),
+                        });
+                    }
 
                     // parse clause count
                     self.skip_whitespace_and_peek()?.ok_or_else(|| {
@@ -179,6 +425,7 @@ impl<R: Read> QdimacsParser<R> {
                             err_span: err.err_span().unwrap_or_else(|| self.err_span()),
                         })?;
 
+                    self.num_variables = num_variables;
                     self.num_clauses = num_clauses;
                     result.set_num_variables(num_variables);
                     result.set_num_clauses(num_clauses);
@@ -200,8 +447,12 @@ impl<R: Read> QdimacsParser<R> {
                 b'a' | b'e' => {
                     self.parse_prefix_line(result)?;
                 }
-                b'-' | (b'0'..=b'9') => {
-                    // end of quantifier prefix
+                b'-' | (b'0'..=b'9') | b'%' => {
+                    // end of quantifier prefix, the end-of-data sentinel is handled by the matrix
+                    return Ok(());
+                }
+                b'w' if self.soft_clauses => {
+                    // end of quantifier prefix, starting with a soft clause
                     return Ok(());
                 }
                 _ => return Err(ParseError::UnexpectedChar { err_span: self.err_offset().into() }),
@@ -211,6 +462,8 @@ impl<R: Read> QdimacsParser<R> {
     }
 
     /// Either `e ...` or `a ...`
+    ///
+    /// Empty scopes, e.g., `e 0`, are dropped, i.e., they are not reported to the result.
     fn parse_prefix_line<Q: FromQdimacs>(&mut self, result: &mut Q) -> Result<(), ParseError> {
         let quant = match self
             .next_byte()?
@@ -220,14 +473,30 @@ impl<R: Read> QdimacsParser<R> {
             b'a' => QuantTy::Forall,
             _ => unreachable!(),
         };
-        let mut vars = Vec::new();
+        let mut vars = std::mem::take(&mut self.buffer.vars);
+        vars.clear();
+        self.buffer.spans.clear();
+        let parsed = self.parse_vars(&mut vars);
+        if parsed.is_ok() && !vars.is_empty() {
+            if self.source_spans {
+                result.quantify_spanned(quant, &vars, &self.buffer.spans);
+            } else {
+                result.quantify(quant, &vars);
+            }
+        }
+        self.buffer.vars = vars;
+        parsed
+    }
+
+    /// Parses variables until the terminating `0`
+    fn parse_vars(&mut self, vars: &mut Vec<Var>) -> Result<(), ParseError> {
         loop {
             self.skip_whitespace_and_peek()?
                 .ok_or_else(|| ParseError::UnexpectedEndOfFile { err_span: self.err_span() })?;
             let start_offset = self.err_offset();
             let var: i32 = self.parse_int()?;
             if var == 0 {
-                break;
+                return Ok(());
             }
             if !(1..=Var::MAX_VAR.to_dimacs()).contains(&var) {
                 return Err(ParseError::VariableOutOfBound {
@@ -236,45 +505,148 @@ impl<R: Read> QdimacsParser<R> {
                     err_span: (start_offset..self.err_offset().saturating_sub(1)).into(),
                 });
             }
+            self.check_declared(var, start_offset)?;
             vars.push(Var::from_dimacs(var));
+            self.push_span(start_offset);
         }
-        result.quantify(quant, &vars);
-        Ok(())
     }
 
     /// Parses clauses until EOF
     fn parse_matrix<Q: FromQdimacs>(&mut self, result: &mut Q) -> Result<(), ParseError> {
-        let mut clause = Vec::new();
-        while (self.skip_whitespace_and_peek()?).is_some() {
+        while let Some(b) = self.skip_whitespace_and_peek()? {
+            if matches!(b, b'a' | b'e') {
+                if !self.relaxed_prefix {
+                    return Err(ParseError::PrefixAfterMatrix {
+                        err_span: self.err_offset().into(),
+                    });
+                }
+                self.parse_prefix_line(result)?;
+                continue;
+            }
+            if b == b'w' && self.soft_clauses {
+                self.parse_soft_clause(result)?;
+                continue;
+            }
+            if b == b'%' {
+                // end-of-data sentinel used by some benchmark sets, the remaining input is ignored
+                break;
+            }
+            let line = self.line;
+            let mut clause = std::mem::take(&mut self.buffer.lits);
             clause.clear();
-            loop {
-                self.skip_whitespace_and_peek()?
-                    .ok_or_else(|| ParseError::UnexpectedEndOfFile { err_span: self.err_span() })?;
-                let start_offset = self.err_offset();
-                let lit: i32 = self.parse_int()?;
-                if lit == 0 {
-                    break;
+            self.buffer.spans.clear();
+            let parsed = self.parse_clause(&mut clause);
+            if parsed.is_ok() {
+                if self.source_spans {
+                    result.add_clause_spanned(&clause, &self.buffer.spans);
+                } else if self.source_lines {
+                    result.add_clause_at(&clause, line);
+                } else {
+                    result.add_clause(&clause);
                 }
-                if !(Lit::MIN_LIT.to_dimacs()..=Lit::MAX_LIT.to_dimacs()).contains(&lit) {
-                    return Err(ParseError::LiteralOutOfBound {
-                        val: lit.into(),
-                        err_span: (start_offset..self.err_offset()).into(),
+                self.num_clauses_read += 1;
+            }
+            let started = !clause.is_empty();
+            self.buffer.lits = clause;
+            match parsed {
+                Err(ParseError::UnexpectedEndOfFile { .. })
+                    if started && self.num_clauses_read < self.num_clauses =>
+                {
+                    return Err(ParseError::NumClausesMismatch {
+                        expected: self.num_clauses,
+                        found: self.num_clauses_read,
+                        at_eof: true,
                     });
                 }
-                clause.push(Lit::from_dimacs(lit));
+                parsed => parsed?,
             }
-            result.add_clause(&clause);
-            self.num_clauses_read += 1;
         }
         Ok(())
     }
 
+    /// `w <weight> <lits> 0`
+    fn parse_soft_clause<Q: FromQdimacs>(&mut self, result: &mut Q) -> Result<(), ParseError> {
+        let w = self.next_byte()?;
+        debug_assert_eq!(w, Some(b'w'));
+        self.skip_whitespace_and_peek()?
+            .ok_or_else(|| ParseError::UnexpectedEndOfFile { err_span: self.err_span() })?;
+        let weight: u64 = self.parse_int().map_err(|err| ParseError::InvalidWeight {
+            err_span: err.err_span().unwrap_or_else(|| self.err_span()),
+        })?;
+        let mut clause = std::mem::take(&mut self.buffer.lits);
+        clause.clear();
+        let parsed = self.parse_clause(&mut clause);
+        if parsed.is_ok() {
+            result.add_soft_clause(weight, &clause);
+        }
+        self.buffer.lits = clause;
+        parsed
+    }
+
+    /// Parses literals until the terminating `0`
+    fn parse_clause(&mut self, clause: &mut Vec<Lit>) -> Result<(), ParseError> {
+        let mut clause_offset = None;
+        loop {
+            self.skip_whitespace_and_peek()?
+                .ok_or_else(|| ParseError::UnexpectedEndOfFile { err_span: self.err_span() })?;
+            let start_offset = self.err_offset();
+            let clause_offset = *clause_offset.get_or_insert(start_offset);
+            let lit: i32 = self.parse_int()?;
+            if lit == 0 {
+                return Ok(());
+            }
+            if !(Lit::MIN_LIT.to_dimacs()..=Lit::MAX_LIT.to_dimacs()).contains(&lit) {
+                return Err(ParseError::LiteralOutOfBound {
+                    val: lit.into(),
+                    token: self.token(),
+                    err_span: (start_offset..self.err_offset()).into(),
+                });
+            }
+            self.check_declared(lit, start_offset)?;
+            clause.push(Lit::from_dimacs(lit));
+            if let Some(max_width) =
+                self.max_clause_width.filter(|&max_width| clause.len() > max_width)
+            {
+                return Err(ParseError::ClauseTooWide {
+                    max_width,
+                    // reduce end offset by one, as last byte was a whitespace
+                    err_span: (clause_offset..self.err_offset().saturating_sub(1)).into(),
+                });
+            }
+            self.push_span(start_offset);
+        }
+    }
+
+    /// In strict mode, checks that the variable of the literal `lit` starting at `start_offset`
+    /// does not exceed the variable count of the header.
+    fn check_declared(&self, lit: i32, start_offset: usize) -> Result<(), ParseError> {
+        if self.strict && lit.unsigned_abs() > self.num_variables {
+            return Err(ParseError::VariableOutOfBound {
+                val: lit.unsigned_abs().into(),
+                // reduce end offset by one, as last byte was a whitespace
+                err_span: (start_offset..self.err_offset().saturating_sub(1)).into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Records the span of the token starting at `start_offset` if source spans are tracked.
+    fn push_span(&mut self, start_offset: usize) {
+        if self.source_spans {
+            // reduce end offset by one, as last byte was a whitespace
+            self.buffer.spans.push((start_offset..self.err_offset().saturating_sub(1)).into());
+        }
+    }
+
     /// Consumes the next byte in the input.
     /// Returns the byte or `None` in the case of EOF.
     fn next_byte(&mut self) -> Result<Option<u8>, ParseError> {
         let byte = self.bytes.next().transpose()?;
-        if byte.is_some() {
+        if let Some(b) = byte {
             self.offset += 1;
+            if b == b'\n' {
+                self.line += 1;
+            }
         }
         Ok(byte)
     }
@@ -296,6 +668,20 @@ impl<R: Read> QdimacsParser<R> {
         Ok(())
     }
 
+    /// Consumes bytes until `until` is found and returns the bytes before it.
+    fn read_until(&mut self, until: u8) -> Result<Vec<u8>, ParseError> {
+        let mut bytes = Vec::new();
+        loop {
+            match self
+                .next_byte()?
+                .ok_or_else(|| ParseError::UnexpectedEndOfFile { err_span: self.err_span() })?
+            {
+                b if b == until => return Ok(bytes),
+                b => bytes.push(b),
+            }
+        }
+    }
+
     /// Skips input bytes until a non-ASCII whitespace character is found.
     /// Returns the first non-ASCII whitespace character (if not EOF).
     fn skip_whitespace_and_peek(&mut self) -> Result<Option<u8>, ParseError> {
@@ -326,11 +712,15 @@ impl<R: Read> QdimacsParser<R> {
         let start_span = self.err_offset();
         let mut parsed: i64 = 0;
         let mut is_negated = false;
+        self.buffer.token.clear();
         while let Some(b) = self.next_byte()? {
+            if !b.is_ascii_whitespace() {
+                self.buffer.token.push(b);
+            }
             match b {
                 b'-' => {
                     if is_negated {
-                        return Err(ParseError::InvalidInt { err_span: self.err_span() });
+                        return Err(self.invalid_int(self.err_span()));
                     }
                     is_negated = true;
                 }
@@ -342,16 +732,12 @@ impl<R: Read> QdimacsParser<R> {
                         parsed
                     } else {
                         // overflow while parsing integer
-                        return Err(ParseError::InvalidInt {
-                            err_span: (start_span..self.err_offset()).into(),
-                        });
+                        return Err(self.invalid_int((start_span..self.err_offset()).into()));
                     }
                 }
                 b => {
                     if !b.is_ascii_whitespace() {
-                        return Err(ParseError::InvalidInt {
-                            err_span: (start_span..self.err_offset()).into(),
-                        });
+                        return Err(self.invalid_int((start_span..self.err_offset()).into()));
                     }
                     break;
                 }
@@ -363,12 +749,29 @@ impl<R: Read> QdimacsParser<R> {
         I::try_from(parsed).map_err(|_| {
             ParseError::LiteralOutOfBound {
                 val: parsed,
+                token: self.token(),
                 // reduce end offset by one, as last byte was a whitespace
                 err_span: (start_span..self.err_offset().saturating_sub(1)).into(),
             }
         })
     }
 
+    /// Consumes the remainder of a malformed integer token and returns the corresponding error.
+    fn invalid_int(&mut self, err_span: SourceSpan) -> ParseError {
+        while let Some(b) = self.peek_byte().filter(|b| !b.is_ascii_whitespace()) {
+            self.buffer.token.push(b);
+            if self.next_byte().is_err() {
+                break;
+            }
+        }
+        ParseError::InvalidInt { token: self.token(), err_span }
+    }
+
+    /// Returns the text of the last parsed integer token.
+    fn token(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.token).into_owned()
+    }
+
     fn err_offset(&self) -> usize {
         self.offset
     }
@@ -378,10 +781,106 @@ impl<R: Read> QdimacsParser<R> {
     }
 }
 
+#[cfg(feature = "mmap")]
+impl QdimacsParser<std::io::Cursor<memmap2::Mmap>> {
+    /// Creates a parser over the memory-mapped file at `path`.
+    /// In contrast to reading the file, the content is not copied into memory.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened or mapped.
+    pub fn from_path(path: &std::path::Path) -> Result<Self, ParseError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapped file must not be modified while parsing, which we cannot prevent.
+        // In the worst case, a concurrent modification leads to an invalid parse result.
+        #[allow(unsafe_code)]
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self::new(std::io::Cursor::new(mmap)))
+    }
+}
+
+/// Buffers a formula such that prefix lines can be merged before clauses are emitted.
+#[derive(Debug, Default)]
+struct DeferredFormula {
+    /// the scopes and the spans of their variables, if reported
+    prefix: Vec<(QuantTy, Vec<Var>, Option<Vec<SourceSpan>>)>,
+    /// the clauses and their location in the input, if reported
+    matrix: Vec<(Vec<Lit>, ClauseSource)>,
+    soft: Vec<(u64, Vec<Lit>)>,
+}
+
+/// The location of a clause in the input, as reported to [`DeferredFormula`].
+#[derive(Debug)]
+enum ClauseSource {
+    Unknown,
+    Line(usize),
+    Spans(Vec<SourceSpan>),
+}
+
+impl DeferredFormula {
+    fn replay<Q: FromQdimacs>(self, result: &mut Q) {
+        for (quant, vars, spans) in &self.prefix {
+            match spans {
+                Some(spans) => result.quantify_spanned(*quant, vars, spans),
+                None => result.quantify(*quant, vars),
+            }
+        }
+        for (clause, source) in &self.matrix {
+            match source {
+                ClauseSource::Unknown => result.add_clause(clause),
+                ClauseSource::Line(line) => result.add_clause_at(clause, *line),
+                ClauseSource::Spans(spans) => result.add_clause_spanned(clause, spans),
+            }
+        }
+        for (weight, clause) in &self.soft {
+            result.add_soft_clause(*weight, clause);
+        }
+    }
+}
+
+impl FromQdimacs for DeferredFormula {
+    fn set_num_variables(&mut self, _: u32) {}
+
+    fn set_num_clauses(&mut self, _: u32) {}
+
+    fn quantify(&mut self, quant: QuantTy, vars: &[Var]) {
+        match self.prefix.last_mut() {
+            Some((last, bound, _)) if *last == quant => bound.extend_from_slice(vars),
+            _ => self.prefix.push((quant, vars.to_owned(), None)),
+        }
+    }
+
+    fn quantify_spanned(&mut self, quant: QuantTy, vars: &[Var], spans: &[SourceSpan]) {
+        match self.prefix.last_mut() {
+            Some((last, bound, Some(bound_spans))) if *last == quant => {
+                bound.extend_from_slice(vars);
+                bound_spans.extend_from_slice(spans);
+            }
+            _ => self.prefix.push((quant, vars.to_owned(), Some(spans.to_owned()))),
+        }
+    }
+
+    fn add_clause(&mut self, lits: &[Lit]) {
+        self.matrix.push((lits.to_owned(), ClauseSource::Unknown));
+    }
+
+    fn add_clause_at(&mut self, lits: &[Lit], line: usize) {
+        self.matrix.push((lits.to_owned(), ClauseSource::Line(line)));
+    }
+
+    fn add_clause_spanned(&mut self, lits: &[Lit], spans: &[SourceSpan]) {
+        self.matrix.push((lits.to_owned(), ClauseSource::Spans(spans.to_owned())));
+    }
+
+    fn add_soft_clause(&mut self, weight: u64, lits: &[Lit]) {
+        self.soft.push((weight, lits.to_owned()));
+    }
+}
+
 impl ParseError {
     fn err_span(&self) -> Option<SourceSpan> {
         match self {
-            ParseError::InvalidInt { err_span }
+            ParseError::InvalidInt { err_span, .. }
             | ParseError::LiteralOutOfBound { err_span, .. } => Some(*err_span),
             _ => None,
         }
@@ -407,7 +906,7 @@ mod test {
             let qdimacs = format!("{input}");
             let reader = Cursor::new(qdimacs);
             let parsed: QCNF = QdimacsParser::new(reader).parse()?;
-            assert_eq!(parsed, input);
+            assert!(parsed.semantically_eq(&input), "{parsed} != {input}");
         }
     }
 
@@ -433,6 +932,28 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn byte_order_mark() -> Result<(), ParseError> {
+        let expected = qcnf_formula![a 1; e 2; 1 -2;];
+        let qdimacs = b"\xEF\xBB\xBFp cnf 2 1\na 1 0\ne 2 0\n1 -2 0\n";
+        assert_eq!(QdimacsParser::new(Cursor::new(qdimacs)).parse::<QCNF>()?, expected);
+        let qdimacs = b"\xEF\xBB\xBFc comment\np cnf 2 1\na 1 0\ne 2 0\n1 -2 0\n";
+        assert_eq!(QdimacsParser::new(Cursor::new(qdimacs)).parse::<QCNF>()?, expected);
+
+        // the byte order mark is only skipped at the start of the input
+        expect_error!(b"\xEF\xBBp cnf 2 1\n", ParseError::UnexpectedChar { .. });
+        expect_error!(b"\n\xEF\xBB\xBFp cnf 2 1\n", ParseError::UnexpectedChar { .. });
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_line_endings() -> Result<(), ParseError> {
+        let qdimacs = "c comment\r\np cnf 2 2\r\na 1 0\r\ne 2 0\r\n1 -2 0\r\n-1\r\n2 0\r\n";
+        let parsed: QCNF = QdimacsParser::new(Cursor::new(qdimacs)).parse()?;
+        assert_eq!(parsed, qcnf_formula![a 1; e 2; 1 -2; -1 2;]);
+        Ok(())
+    }
+
     #[test]
     fn no_matrix() -> Result<(), ParseError> {
         let qdimacs = "p cnf 10 0\ne 1 2 3 0\na 4 5 6 0\n";
@@ -442,6 +963,63 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn soft_clauses() -> Result<(), ParseError> {
+        let qdimacs = "p cnf 3 1\na 1 0\ne 2 3 0\nw 5 1 2 0\n-2 3 0\nw 0 -3 0\n";
+        let parsed: QCNF = QdimacsParser::new(Cursor::new(qdimacs)).soft_clauses(true).parse()?;
+        let mut expected = qcnf_formula![a 1; e 2 3; -2 3;];
+        expected.soft = vec![
+            (5, vec![Lit::from_dimacs(1), Lit::from_dimacs(2)]),
+            (0, vec![Lit::from_dimacs(-3)]),
+        ];
+        assert_eq!(parsed, expected);
+
+        // roundtrip
+        let reparsed: QCNF =
+            QdimacsParser::new(Cursor::new(format!("{parsed}"))).soft_clauses(true).parse()?;
+        assert_eq!(reparsed, parsed);
+        let mut wrapped = Vec::new();
+        parsed.write_dimacs_wrapped(&mut wrapped, 4)?;
+        let reparsed: QCNF = QdimacsParser::new(wrapped.as_slice()).soft_clauses(true).parse()?;
+        assert_eq!(reparsed, parsed);
+
+        // soft clauses are rejected unless enabled
+        expect_error!(qdimacs, ParseError::UnexpectedChar { .. });
+        expect_error!(b"p cnf 1 0\nw -1 1 0\n", ParseError::UnexpectedChar { .. });
+        let negative_weight =
+            QdimacsParser::new(&b"p cnf 1 0\nw -1 1 0\n"[..]).soft_clauses(true).parse::<QCNF>();
+        assert!(matches!(negative_weight, Err(ParseError::InvalidWeight { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn end_of_data_sentinel() -> Result<(), ParseError> {
+        let qdimacs = "p cnf 2 1\na 1 0\ne 2 0\n1 2 0\n%\n0\n";
+        let parsed: QCNF = QdimacsParser::new(Cursor::new(qdimacs)).parse()?;
+        assert_eq!(parsed, qcnf_formula![a 1; e 2; 1 2;]);
+
+        // the sentinel may directly follow the prefix
+        let qdimacs = "p cnf 1 0\ne 1 0\n%\n0\n";
+        let parsed: QCNF = QdimacsParser::new(Cursor::new(qdimacs)).parse()?;
+        assert_eq!(parsed.prefix, [(QuantTy::Exists, vec![Var::from_dimacs(1)])]);
+        assert!(parsed.matrix.is_empty());
+        expect_error!(b"p cnf 1 0\ne 1 %\n0\n", ParseError::InvalidInt { .. });
+
+        // the clauses have to be complete before the sentinel
+        expect_error!(b"p cnf 2 1\n1 2\n%\n0\n", ParseError::InvalidInt { .. });
+        expect_error!(b"p cnf 2 2\n1 2 0\n%\n0\n", ParseError::NumClausesMismatch { .. });
+        Ok(())
+    }
+
+    #[test]
+    fn header_spellings() -> Result<(), ParseError> {
+        for qdimacs in ["p cnf 2 1\na 1 0\ne 2 0\n1 2 0\n", "p qcnf 2 1\na 1 0\ne 2 0\n1 2 0\n"] {
+            let parsed: QCNF = QdimacsParser::new(Cursor::new(qdimacs)).parse()?;
+            assert_eq!(parsed, qcnf_formula![a 1; e 2; 1 2;]);
+        }
+        Ok(())
+    }
+
     #[test]
     fn no_prefix() -> Result<(), ParseError> {
         let qdimacs = "p cnf 10 2\n1 2 3 0\n4 5 6 0\n";
@@ -482,7 +1060,7 @@ mod test {
         let qdimacs = format!("{orig}");
         let reader = Cursor::new(qdimacs);
         let parsed: QCNF = QdimacsParser::new(reader).parse()?;
-        assert_eq!(orig, parsed);
+        assert!(orig.semantically_eq(&parsed), "{orig} != {parsed}");
         Ok(())
     }
 
@@ -492,6 +1070,27 @@ mod test {
         expect_error!(b"c comment\nc comments\n\n", ParseError::MissingHeader);
     }
 
+    #[test]
+    fn malformed_int_token() {
+        let cases: [(&[u8], &str); 6] = [
+            (b"p cnf 2 1\n1 2a3 0\n", "2a3"),
+            (b"p cnf 2 1\n1 --2 0\n", "--2"),
+            (b"p cnf 2 1\n1 -x2 0\n", "-x2"),
+            (b"p cnf 2 1\n99999999999999999999 0\n", "99999999999999999999"),
+            (b"p cnf 2 1\n1 2147483648 0\n", "2147483648"),
+            (b"p cnf 2 1\n-2147483648 0\n", "-2147483648"),
+        ];
+        for (input, expected) in cases {
+            match QdimacsParser::new(input).parse::<QCNF>() {
+                Err(
+                    ParseError::InvalidInt { token, .. }
+                    | ParseError::LiteralOutOfBound { token, .. },
+                ) => assert_eq!(token, expected),
+                result => panic!("Unexpected result {result:?}"),
+            }
+        }
+    }
+
     #[test]
     fn out_of_bound() {
         // i32::MAX = 2147483647 is the largest representable literal
@@ -500,6 +1099,25 @@ mod test {
         expect_error!(b"p cnf 0 0\n1 -2147483648 3 0", ParseError::LiteralOutOfBound { .. });
     }
 
+    #[test]
+    fn strict() -> Result<(), ParseError> {
+        let input = b"p cnf 3 2\na 1 0\ne 2 3 0\n1 2 0\n-1 -5 3 0\n";
+        let parsed: QCNF = QdimacsParser::new(Cursor::new(input)).parse()?;
+        assert_eq!(parsed.matrix.len(), 2);
+
+        let err = QdimacsParser::new(Cursor::new(input)).strict(true).parse::<QCNF>().unwrap_err();
+        let ParseError::VariableOutOfBound { val, err_span } = err else {
+            panic!("Unexpected error {err:?}");
+        };
+        assert_eq!(val, 5);
+        assert_eq!(&input[err_span.offset()..err_span.offset() + err_span.len()], b"-5");
+
+        let input = b"p cnf 3 1\na 1 0\ne 2 4 0\n1 2 0\n";
+        let err = QdimacsParser::new(Cursor::new(input)).strict(true).parse::<QCNF>().unwrap_err();
+        assert!(matches!(err, ParseError::VariableOutOfBound { val: 4, .. }), "{err:?}");
+        Ok(())
+    }
+
     #[test]
     fn end_of_file() {
         expect_error!(b"p cnf 0 0\n1 2 3 0\n-1 2 3", ParseError::UnexpectedEndOfFile { .. });
@@ -515,6 +1133,10 @@ mod test {
             b"p dnf 2 2",
             ParseError::InvalidHeader { reason: HeaderError::InvalidPrefix, .. }
         );
+        expect_error!(
+            b"p qdnf 2 2",
+            ParseError::InvalidHeader { reason: HeaderError::InvalidPrefix, .. }
+        );
         expect_error!(
             b"pcnf 2 2",
             ParseError::InvalidHeader { reason: HeaderError::InvalidPrefix, .. }
@@ -527,6 +1149,196 @@ mod test {
             b"p cnf 2 -2",
             ParseError::InvalidHeader { reason: HeaderError::InvalidClauseCount, .. }
         );
+        expect_error!(
+            b"p cnf 4000000000 1",
+            ParseError::VariableOutOfBound { val: 4_000_000_000, .. }
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn from_path() -> Result<(), ParseError> {
+        let path =
+            std::env::temp_dir().join(format!("booleanium-mmap-{}.qdimacs", std::process::id()));
+        let orig = qcnf_formula![
+            e 1; a 2; e 3;
+            -1 2 -3;
+            2 3;
+        ];
+        std::fs::write(&path, format!("{orig}"))?;
+        let parsed: Result<QCNF, _> = QdimacsParser::from_path(&path).and_then(|mut p| p.parse());
+        std::fs::remove_file(&path)?;
+        assert_eq!(parsed?, orig);
+        Ok(())
+    }
+
+    #[test]
+    fn comments() -> Result<(), ParseError> {
+        let qdimacs = "c source foo.qdimacs\nc seed   42\r\nc\np cnf 1 1\ne 1 0\n1 0\n";
+        let mut parser = QdimacsParser::new(Cursor::new(qdimacs));
+        let _: QCNF = parser.parse()?;
+        assert!(parser.comments().is_empty());
+
+        let mut parser = QdimacsParser::new(Cursor::new(qdimacs)).collect_comments(true);
+        let _: QCNF = parser.parse()?;
+        assert_eq!(parser.comments(), ["source foo.qdimacs", "seed   42", ""]);
+        assert_eq!(
+            parser.comment_metadata().collect::<Vec<_>>(),
+            [("source", "foo.qdimacs"), ("seed", "42")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reuse_buffer() -> Result<(), ParseError> {
+        let mut buffer = ParseBuffer::default();
+        let mut qcnf = QCNF::default();
+        for qdimacs in ["p cnf 2 1\na 1 0\ne 2 0\n1 2 0\n", "p cnf 1 1\ne 1 0\n-1 0\n"] {
+            qcnf.clear();
+            let mut parser = QdimacsParser::with_buffer(Cursor::new(qdimacs), buffer);
+            parser.parse_into(&mut qcnf)?;
+            buffer = parser.into_buffer();
+        }
+        assert_eq!(qcnf, qcnf_formula![e 1; -1;]);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_scope() -> Result<(), ParseError> {
+        let qdimacs = "p cnf 2 1\ne 0\na 1 0\ne 2 0\n1 2 0\n";
+        let reader = Cursor::new(qdimacs);
+        let parsed: QCNF = QdimacsParser::new(reader).parse()?;
+        assert_eq!(parsed, qcnf_formula![a 1; e 2; 1 2;]);
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_after_matrix() -> Result<(), ParseError> {
+        let qdimacs = "p cnf 3 2\ne 1 0\n1 2 0\na 2 0\ne 3 0\n-2 3 0\n";
+        expect_error!(qdimacs, ParseError::PrefixAfterMatrix { .. });
+
+        let reader = Cursor::new(qdimacs);
+        let parsed: QCNF = QdimacsParser::new(reader).relaxed_prefix(true).parse()?;
+        let expected = qcnf_formula![
+            e 1; a 2; e 3;
+            1 2;
+            -2 3;
+        ];
+        assert_eq!(parsed, expected);
+
+        // late scopes with the same quantifier are merged
+        let qdimacs = "p cnf 2 1\ne 1 0\n1 2 0\ne 2 0\n";
+        let reader = Cursor::new(qdimacs);
+        let parsed: QCNF = QdimacsParser::new(reader).relaxed_prefix(true).parse()?;
+        assert_eq!(parsed, qcnf_formula![e 1 2; 1 2;]);
+        Ok(())
+    }
+
+    #[test]
+    fn source_spans() -> Result<(), ParseError> {
+        /// The spans reported for every scope and clause.
+        #[derive(Debug, Default)]
+        struct Spans(Vec<Vec<SourceSpan>>);
+
+        impl FromQdimacs for Spans {
+            fn set_num_variables(&mut self, _variables: u32) {}
+            fn set_num_clauses(&mut self, _clauses: u32) {}
+            fn quantify(&mut self, _quant: QuantTy, _vars: &[Var]) {
+                unreachable!("scopes are reported with their source spans");
+            }
+            fn add_clause(&mut self, _lits: &[Lit]) {
+                unreachable!("clauses are reported with their source spans");
+            }
+            fn quantify_spanned(&mut self, _quant: QuantTy, vars: &[Var], spans: &[SourceSpan]) {
+                assert_eq!(vars.len(), spans.len());
+                self.0.push(spans.to_vec());
+            }
+            fn add_clause_spanned(&mut self, lits: &[Lit], spans: &[SourceSpan]) {
+                assert_eq!(lits.len(), spans.len());
+                self.0.push(spans.to_vec());
+            }
+        }
+
+        let tokens = |qdimacs: &str,
+                      relaxed_prefix: bool|
+         -> Result<Vec<Vec<String>>, ParseError> {
+            let parsed: Spans = QdimacsParser::new(Cursor::new(qdimacs))
+                .relaxed_prefix(relaxed_prefix)
+                .source_spans(true)
+                .parse()?;
+            Ok(parsed
+                .0
+                .iter()
+                .map(|spans| {
+                    spans
+                        .iter()
+                        .map(|span| qdimacs[span.offset()..span.offset() + span.len()].to_owned())
+                        .collect()
+                })
+                .collect())
+        };
+
+        let qdimacs = "p cnf 12 2\na 1  12 0\ne 3 0\n-12 3 0 -1\n3 0\n";
+        assert_eq!(
+            tokens(qdimacs, false)?,
+            [vec!["1", "12"], vec!["3"], vec!["-12", "3"], vec!["-1", "3"]]
+        );
+
+        // late scopes are merged with their spans
+        let qdimacs = "p cnf 3 2\ne 1 0\n1 2 0\ne 2 0\na 3 0\n-3 0\n";
+        assert_eq!(tokens(qdimacs, true)?, [vec!["1", "2"], vec!["3"], vec!["1", "2"], vec!["-3"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn buffered_reads() -> Result<(), ParseError> {
+        /// Counts the calls to [`Read::read`] of the inner reader.
+        struct CountingReader<R> {
+            inner: R,
+            reads: usize,
+        }
+
+        impl<R: Read> Read for &mut CountingReader<R> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.reads += 1;
+                self.inner.read(buf)
+            }
+        }
+
+        let num_clauses = 10_000;
+        let mut input = format!("p cnf 3 {num_clauses}\na 1 0\ne 2 3 0\n");
+        for _ in 0..num_clauses {
+            input.push_str("1 -2 3 0\n");
+        }
+        let mut reader = CountingReader { inner: Cursor::new(input.as_bytes()), reads: 0 };
+        let parsed: QCNF = QdimacsParser::new(&mut reader).parse()?;
+        assert_eq!(parsed.matrix.len(), num_clauses);
+        // the input is read in chunks instead of byte by byte
+        assert!(reader.reads < input.len() / 1000, "{} reads", reader.reads);
+        Ok(())
+    }
+
+    #[test]
+    fn max_clause_width() -> Result<(), ParseError> {
+        let input = b"p cnf 4 2\na 1 0\ne 2 3 4 0\n1 2 0\n-1 2 3 4 0\n";
+        let parse = |max_width| {
+            QdimacsParser::new(Cursor::new(&input)).max_clause_width(max_width).parse::<QCNF>()
+        };
+        assert_eq!(parse(4)?.matrix.len(), 2);
+        let err = parse(3).unwrap_err();
+        let ParseError::ClauseTooWide { max_width, err_span } = err else {
+            panic!("Unexpected error {err:?}");
+        };
+        assert_eq!(max_width, 3);
+        // the span covers the clause up to the first literal exceeding the width
+        assert_eq!(&input[err_span.offset()..err_span.offset() + err_span.len()], b"-1 2 3 4");
+        // the configured width is reported, not the width of the clause
+        let ParseError::ClauseTooWide { max_width, .. } = parse(1).unwrap_err() else {
+            panic!("expected a too wide clause");
+        };
+        assert_eq!(max_width, 1);
+        assert_eq!(parse(1).unwrap_err().to_string(), "Clause has more than 1 literals");
         Ok(())
     }
 
@@ -534,7 +1346,17 @@ mod test {
     fn num_clauses() {
         expect_error!(
             b"p cnf 3 2\n1 -2 0\n2 -3 0\n3 -1 0\n",
-            ParseError::NumClausesMismatch { expected: 2, found: 3 }
+            ParseError::NumClausesMismatch { expected: 2, found: 3, at_eof: false }
+        );
+        // the clauses are missing
+        expect_error!(
+            b"p cnf 10 2\na 1 0\ne 2 0\n",
+            ParseError::NumClausesMismatch { expected: 2, found: 0, at_eof: false }
+        );
+        // the second clause is truncated
+        expect_error!(
+            b"p cnf 10 2\na 1 0\ne 2 0\n1 2 0\n-1 ",
+            ParseError::NumClausesMismatch { expected: 2, found: 1, at_eof: true }
         );
     }
 }
@@ -1,4 +1,5 @@
 use crate::literal::Var;
+use alloc::vec::Vec;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QuantTy {
@@ -37,8 +38,8 @@ pub struct Scope {
     ty: ScopeTy,
 }
 
-impl std::fmt::Display for QuantTy {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for QuantTy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             QuantTy::Exists => write!(f, "e"),
             QuantTy::Forall => write!(f, "a"),
@@ -46,8 +47,8 @@ impl std::fmt::Display for QuantTy {
     }
 }
 
-impl std::fmt::Display for Scope {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Scope {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self.ty {
             ScopeTy::Unbound => return Ok(()),
             ScopeTy::Existential => write!(f, "e")?,
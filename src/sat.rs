@@ -1,6 +1,12 @@
 //! Generic SAT solver interface that supports incremental solving
 
 use derivative::Derivative;
+use std::{
+    fmt,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+use tracing::error;
 
 use crate::{
     datastructure::VarVec,
@@ -35,7 +41,89 @@ pub(crate) trait SatSolver: Default {
     }
 }
 
-pub(crate) trait SatSolverLit: Copy + Eq + std::ops::Not<Output = Self> {}
+pub(crate) trait SatSolverLit: Copy + Eq + std::ops::Not<Output = Self> {
+    /// The DIMACS representation of the literal, i.e., a non-zero integer whose sign is the polarity.
+    fn to_dimacs(self) -> isize;
+}
+
+/// Shared sink that records the clauses and solver calls of one or more [`LookupSolver`]s.
+///
+/// The trace is written in the incremental DIMACS format over the variables of the SAT solver:
+/// every clause is terminated by `0` and every solver call is recorded as `a <assumptions> 0`
+/// followed by a `c result` comment.
+/// Every solver that writes to the trace gets a unique id, a `c solver <id> <name>` comment
+/// precedes its lines whenever the traced solver changes.
+#[derive(Clone)]
+pub(crate) struct SatTrace {
+    sink: Arc<Mutex<TraceSink>>,
+}
+
+struct TraceSink {
+    writer: Box<dyn Write + Send>,
+    /// the number of solvers attached to the trace
+    solvers: usize,
+    /// the id of the solver that wrote the last line
+    current: Option<usize>,
+}
+
+impl SatTrace {
+    pub(crate) fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            sink: Arc::new(Mutex::new(TraceSink {
+                writer: Box::new(writer),
+                solvers: 0,
+                current: None,
+            })),
+        }
+    }
+
+    fn attach(&self, name: String) -> TraceHandle {
+        let mut sink = self.sink.lock().unwrap();
+        let id = sink.solvers;
+        sink.solvers += 1;
+        TraceHandle { trace: self.clone(), id, name }
+    }
+}
+
+impl fmt::Debug for SatTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SatTrace").finish_non_exhaustive()
+    }
+}
+
+/// The connection of a single solver to a [`SatTrace`].
+struct TraceHandle {
+    trace: SatTrace,
+    id: usize,
+    name: String,
+}
+
+impl TraceHandle {
+    fn write_line(&self, line: &str) {
+        let mut sink = self.trace.sink.lock().unwrap();
+        let result = (|| {
+            if sink.current != Some(self.id) {
+                sink.current = Some(self.id);
+                writeln!(sink.writer, "c solver {} {}", self.id, self.name)?;
+            }
+            writeln!(sink.writer, "{line}")?;
+            sink.writer.flush()
+        })();
+        if let Err(err) = result {
+            error!("failed to write SAT trace: {err}");
+        }
+    }
+
+    fn write_lits<L: SatSolverLit>(&self, prefix: &str, lits: &[L]) {
+        let mut line = prefix.to_string();
+        for lit in lits {
+            line.push_str(&lit.to_dimacs().to_string());
+            line.push(' ');
+        }
+        line.push('0');
+        self.write_line(&line);
+    }
+}
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -44,15 +132,22 @@ pub(crate) struct LookupSolver<S: SatSolver> {
     sat_solver: S,
     #[derivative(Debug = "ignore")]
     var_lookup: VarVec<Option<S::Lit>>,
+    #[derivative(Debug = "ignore")]
+    trace: Option<TraceHandle>,
 }
 
 impl<S: SatSolver> Default for LookupSolver<S> {
     fn default() -> Self {
-        Self { sat_solver: Default::default(), var_lookup: VarVec::default() }
+        Self { sat_solver: Default::default(), var_lookup: VarVec::default(), trace: None }
     }
 }
 
 impl<S: SatSolver> LookupSolver<S> {
+    /// Records all subsequent clauses and solver calls in `trace`, `name` identifies the solver in the trace.
+    pub(crate) fn set_trace(&mut self, trace: &SatTrace, name: impl Into<String>) {
+        self.trace = Some(trace.attach(name.into()));
+    }
+
     pub(crate) fn set_var_count(&mut self, count: usize) {
         self.var_lookup.set_var_count(count);
     }
@@ -100,11 +195,23 @@ impl<S: SatSolver> SatSolver for LookupSolver<S> {
     }
 
     fn add_clause(&mut self, lits: &[Self::Lit]) {
+        if let Some(trace) = &self.trace {
+            trace.write_lits("", lits);
+        }
         self.sat_solver.add_clause(lits);
     }
 
     fn solve_with_assumptions(&mut self, assumptions: &[Self::Lit]) -> Result<bool, Self::Err> {
-        self.sat_solver.solve_with_assumptions(assumptions)
+        let result = self.sat_solver.solve_with_assumptions(assumptions);
+        if let Some(trace) = &self.trace {
+            trace.write_lits("a ", assumptions);
+            match &result {
+                Ok(true) => trace.write_line("c result sat"),
+                Ok(false) => trace.write_line("c result unsat"),
+                Err(err) => trace.write_line(&format!("c result error: {err}")),
+            }
+        }
+        result
     }
 
     fn model(&mut self) -> Option<&[Self::Lit]> {
@@ -1,11 +1,18 @@
 //! Implementation of SAT solver interface for (cryptominisat)[https://crates.io/crates/cryptominisat].
 
-use std::convert::Infallible;
-
 use cryptominisat::Lbool;
+use thiserror::Error;
 
 use super::{SatSolver, SatSolverLit};
 
+/// Errors reported by [`CryptoMiniSat`].
+#[derive(Debug, Error)]
+pub(crate) enum CryptoMiniSatError {
+    /// The solver finished without a result, e.g., because it was interrupted or ran out of resources.
+    #[error("cryptominisat returned an undefined result")]
+    Undefined,
+}
+
 pub(crate) struct CryptoMiniSat {
     solver: cryptominisat::Solver,
     model: Vec<cryptominisat::Lit>,
@@ -13,7 +20,7 @@ pub(crate) struct CryptoMiniSat {
 
 impl SatSolver for CryptoMiniSat {
     type Lit = cryptominisat::Lit;
-    type Err = Infallible;
+    type Err = CryptoMiniSatError;
 
     fn add_variables(&mut self, variables: usize) {
         self.solver.new_vars(variables)
@@ -32,7 +39,7 @@ impl SatSolver for CryptoMiniSat {
         match result {
             Lbool::True => Ok(true),
             Lbool::False => Ok(false),
-            Lbool::Undef => todo!(),
+            Lbool::Undef => Err(CryptoMiniSatError::Undefined),
         }
     }
 
@@ -65,7 +72,16 @@ impl Default for CryptoMiniSat {
     }
 }
 
-impl SatSolverLit for cryptominisat::Lit {}
+impl SatSolverLit for cryptominisat::Lit {
+    fn to_dimacs(self) -> isize {
+        let var = isize::try_from(self.var()).unwrap() + 1;
+        if self.isneg() {
+            -var
+        } else {
+            var
+        }
+    }
+}
 
 #[cfg(test)]
 mod test {
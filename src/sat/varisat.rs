@@ -51,7 +51,11 @@ impl Default for Varisat {
     }
 }
 
-impl SatSolverLit for varisat::Lit {}
+impl SatSolverLit for varisat::Lit {
+    fn to_dimacs(self) -> isize {
+        varisat::Lit::to_dimacs(self)
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -0,0 +1,88 @@
+//! Entry point that parses and solves a QDIMACS instance without panicking.
+
+use crate::{
+    incdet::IncDet,
+    qcnf::QCNF,
+    qdimacs::{ParseError, QdimacsParser},
+    SolverResult,
+};
+use miette::Diagnostic;
+use std::collections::HashSet;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum SolveError {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Parse(#[from] ParseError),
+
+    #[error("Variable {var} is not bound in the quantifier prefix")]
+    UnboundVariable { var: i32 },
+
+    #[error("Only 2QBF is currently supported, but the prefix has {scopes} scopes")]
+    UnsupportedPrefix { scopes: usize },
+
+    #[error("The SAT solver failed")]
+    SatBackend(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("Solving was interrupted before the result was known")]
+    Timeout,
+}
+
+/// Parses the QDIMACS `input` and solves it.
+/// In contrast to [`IncDet::solve`], unsupported inputs are reported as errors, see [`IncDet::try_solve`].
+///
+/// # Errors
+///
+/// Returns a [`SolveError`] if the input is not valid QDIMACS or is not supported by the solver.
+pub fn try_solve(input: &[u8]) -> Result<SolverResult, SolveError> {
+    let qcnf: QCNF = QdimacsParser::new(input).parse()?;
+
+    let bound: HashSet<_> = qcnf.prefix.iter().flat_map(|(_, vars)| vars).collect();
+    if let Some(lit) = qcnf.matrix.iter().flatten().find(|lit| !bound.contains(&lit.var())) {
+        return Err(SolveError::UnboundVariable { var: lit.var().to_dimacs() });
+    }
+
+    IncDet::from_qcnf(&qcnf).try_solve()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn doesnt_crash(s in ".*") {
+            let _ = try_solve(s.as_bytes());
+        }
+
+        #[test]
+        fn doesnt_crash_on_qcnf(qcnf in crate::qcnf::strategy::qcnf(1..4, 1..5, 0..20, 0..5)) {
+            let _ = try_solve(format!("{qcnf}").as_bytes());
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            try_solve(b"p cnf 1 1\n1 0\n"),
+            Err(SolveError::UnboundVariable { var: 1 })
+        ));
+        assert!(matches!(
+            try_solve(b"p cnf 3 1\ne 1 0\na 2 0\ne 3 0\n1 2 3 0\n"),
+            Err(SolveError::UnsupportedPrefix { scopes: 3 })
+        ));
+        assert!(matches!(try_solve(b"p cnf 1 1\n"), Err(SolveError::Parse(_))));
+    }
+
+    #[test]
+    fn solve() {
+        assert!(matches!(
+            try_solve(b"p cnf 2 2\na 1 0\ne 2 0\n1 -2 0\n-1 2 0\n"),
+            Ok(SolverResult::Satisfiable)
+        ));
+        assert!(matches!(try_solve(b"p cnf 1 1\na 1 0\n1 0\n"), Ok(SolverResult::Unsatisfiable)));
+        assert!(matches!(try_solve(b"p cnf 0 1\n0\n"), Ok(SolverResult::Unsatisfiable)));
+    }
+}